@@ -0,0 +1,24 @@
+//! Generates the compact city index `data/cities.tsv.gz` embedded by feature `city-index`
+//! from a city list of bulk.openweathermap.org (or any other compact index to be loaded by
+//! `CityIndex::from_compact()`).
+//!
+//! ```sh
+//! cargo run --example city_index --features city-index -- current.city.list.min.json.gz
+//! ```
+
+use openweathermap::cities::CityIndex;
+use std::env;
+use std::fs::File;
+
+fn main() -> Result<(), String> {
+    let list = env::args()
+        .nth(1)
+        .ok_or("usage: city_index <city list .json.gz> [<index .tsv.gz>]")?;
+    let output = env::args()
+        .nth(2)
+        .unwrap_or_else(|| "data/cities.tsv.gz".to_string());
+    let index = CityIndex::from_gz(File::open(&list).map_err(|e| e.to_string())?)?;
+    index.write_compact(File::create(&output).map_err(|e| e.to_string())?)?;
+    println!("{} cities written to {}", index.cities().len(), output);
+    Ok(())
+}
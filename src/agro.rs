@@ -0,0 +1,313 @@
+//! Soil data, weather and satellite imagery of your fields
+//! (see [Agro Monitoring API](https://agromonitoring.com/api)).
+//!
+//! ```no_run
+//! use openweathermap::agro::{Agro, NewPolygon};
+//! use openweathermap::Coord;
+//!
+//! let agro = Agro::new("<APIKEY>");
+//! let field = agro
+//!     .create(&NewPolygon::new(
+//!         "Field 1",
+//!         &[
+//!             Coord { lat: 37.6683, lon: -121.1958 },
+//!             Coord { lat: 37.6683, lon: -121.1779 },
+//!             Coord { lat: 37.6565, lon: -121.1779 },
+//!             Coord { lat: 37.6565, lon: -121.1958 },
+//!         ],
+//!     ))
+//!     .unwrap();
+//! let soil = agro.soil(&field.id).unwrap();
+//! println!("soil moisture is {} m³/m³", soil.moisture);
+//! ```
+
+use crate::fetch::{parse, request};
+use crate::{Clouds, Coord, Weather, Wind};
+use reqwest::Method;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Base URL of the Agro Monitoring API
+const AGRO: &str = "http://api.agromonitoring.com";
+
+/// GeoJSON polygon geometry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Geometry {
+    /// Geometry type (always `"Polygon"`)
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Linear rings of `[longitude, latitude]` positions, first and last position are equal
+    pub coordinates: Vec<Vec<[f64; 2]>>,
+}
+
+/// GeoJSON feature wrapping a polygon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeoJson {
+    /// Feature type (always `"Feature"`)
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Feature properties
+    #[serde(default)]
+    pub properties: Value,
+    /// Polygon geometry
+    pub geometry: Geometry,
+}
+
+/// Polygon to create
+#[derive(Serialize, Debug, Clone)]
+pub struct NewPolygon {
+    /// Name of the polygon
+    pub name: String,
+    /// Outline of the polygon
+    pub geo_json: GeoJson,
+}
+
+impl NewPolygon {
+    /// Polygon with the given outline. The outline gets closed if its last point
+    /// differs from the first one.
+    pub fn new(name: &str, outline: &[Coord]) -> NewPolygon {
+        let mut ring: Vec<[f64; 2]> = outline.iter().map(|c| [c.lon, c.lat]).collect();
+        if let (Some(first), Some(last)) = (ring.first().copied(), ring.last().copied()) {
+            if first != last {
+                ring.push(first);
+            }
+        }
+        NewPolygon {
+            name: name.to_string(),
+            geo_json: GeoJson {
+                type_: "Feature".to_string(),
+                properties: Value::Object(Default::default()),
+                geometry: Geometry {
+                    type_: "Polygon".to_string(),
+                    coordinates: vec![ring],
+                },
+            },
+        }
+    }
+}
+
+/// Serializes a `Coord` as `[longitude, latitude]` position.
+fn to_position<S: Serializer>(coord: &Coord, serializer: S) -> Result<S::Ok, S::Error> {
+    [coord.lon, coord.lat].serialize(serializer)
+}
+
+/// Deserializes a `[longitude, latitude]` position into a `Coord`.
+fn position<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Coord, D::Error> {
+    let [lon, lat] = <[f64; 2]>::deserialize(deserializer)?;
+    Ok(Coord { lon, lat })
+}
+
+/// Registered polygon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Polygon {
+    /// Polygon ID
+    pub id: String,
+    /// Name of the polygon
+    pub name: String,
+    /// Outline of the polygon
+    pub geo_json: GeoJson,
+    /// Geographical center of the polygon
+    #[serde(serialize_with = "to_position", deserialize_with = "position")]
+    pub center: Coord,
+    /// Area of the polygon, ha
+    pub area: f64,
+}
+
+/// Current soil data of a polygon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Soil {
+    /// Time of data calculation, unix, UTC
+    pub dt: i64,
+    /// Temperature on the surface, Kelvin
+    pub t0: f64,
+    /// Temperature at a depth of 10 cm, Kelvin
+    pub t10: f64,
+    /// Soil moisture, m³/m³
+    pub moisture: f64,
+}
+
+/// Detailed weather report of a polygon (unlike `Main` without perceived temperature)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgroMain {
+    /// Temperature, Kelvin
+    pub temp: f64,
+    /// Atmospheric pressure, hPa
+    pub pressure: f64,
+    /// Humidity, %
+    pub humidity: f64,
+    /// Minimum temperature at the moment, Kelvin
+    pub temp_min: f64,
+    /// Maximum temperature at the moment, Kelvin
+    pub temp_max: f64,
+    /// Atmospheric pressure on the sea level, hPa
+    pub sea_level: Option<f64>,
+    /// Atmospheric pressure on the ground level, hPa
+    pub grnd_level: Option<f64>,
+}
+
+/// Current weather of a polygon.
+/// Units are always standard (Kelvin, meter/sec).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgroWeather {
+    /// Time of data calculation, unix, UTC
+    pub dt: i64,
+    /// vector with one item of weather condition descriptions
+    pub weather: Vec<Weather>,
+    /// detailed weather report
+    pub main: AgroMain,
+    /// detailed wind report
+    pub wind: Wind,
+    /// detailed clouds report
+    pub clouds: Clouds,
+}
+
+/// Position of the sun when a satellite image was taken
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SunPosition {
+    /// Sun azimuth, degrees
+    pub azimuth: f64,
+    /// Sun elevation, degrees
+    pub elevation: f64,
+}
+
+/// URLs to the products of a satellite image
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ImageUrls {
+    /// True color image
+    pub truecolor: Option<String>,
+    /// False color image
+    pub falsecolor: Option<String>,
+    /// Normalized Difference Vegetation Index
+    pub ndvi: Option<String>,
+    /// Enhanced Vegetation Index
+    pub evi: Option<String>,
+}
+
+/// Satellite image of a polygon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SatelliteImage {
+    /// Time of acquisition, unix, UTC
+    pub dt: i64,
+    /// Satellite name (e.g. `"Landsat 8"` or `"Sentinel-2"`)
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Useful area in relation to the polygon, %
+    pub dc: f64,
+    /// Cloud coverage, %
+    pub cl: f64,
+    /// sun position at acquisition
+    pub sun: SunPosition,
+    /// PNG image URLs
+    pub image: ImageUrls,
+    /// Tile URL templates (with `{z}`, `{x}` and `{y}` placeholders)
+    pub tile: ImageUrls,
+    /// URLs of index statistics (see `Agro::stats()`)
+    pub stats: ImageUrls,
+}
+
+/// Statistics of a vegetation index over a polygon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexStats {
+    /// Minimum value
+    pub min: f64,
+    /// Maximum value
+    pub max: f64,
+    /// Mean value
+    pub mean: f64,
+    /// Median value
+    pub median: f64,
+    /// Standard deviation
+    pub std: f64,
+    /// First quartile
+    pub p25: f64,
+    /// Third quartile
+    pub p75: f64,
+    /// Number of pixels
+    pub num: u64,
+}
+
+/// Access to the polygons registered with an API key.
+pub struct Agro {
+    url: String,
+    api_key: String,
+}
+
+impl Agro {
+    /// Access the Agro Monitoring API.
+    /// #### Parameters
+    /// - `api_key`: Your Agro API key which you can get [here](https://agromonitoring.com/price)
+    pub fn new(api_key: &str) -> Agro {
+        Agro::with_base_url(AGRO, api_key)
+    }
+
+    /// Access another server (e.g. a local mock server for testing).
+    /// #### Parameters
+    /// - `base_url`: URL of the server without trailing slash (e.g. `"http://127.0.0.1:8080"`)
+    /// - `api_key`: Your Agro API key
+    pub fn with_base_url(base_url: &str, api_key: &str) -> Agro {
+        Agro {
+            url: format!("{}/agro/1.0", base_url),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Creates a polygon and returns it including its ID.
+    pub fn create(&self, polygon: &NewPolygon) -> Result<Polygon, String> {
+        parse(request(
+            Method::POST,
+            &format!("{}/polygons?appid={}", self.url, self.api_key),
+            Some(polygon),
+        )?)
+    }
+
+    /// Lists all polygons.
+    pub fn list(&self) -> Result<Vec<Polygon>, String> {
+        self.get(&format!("{}/polygons?appid={}", self.url, self.api_key))
+    }
+
+    /// Deletes a polygon.
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        request::<()>(
+            Method::DELETE,
+            &format!("{}/polygons/{}?appid={}", self.url, id, self.api_key),
+            None,
+        )
+        .map(|_| ())
+    }
+
+    /// Gets the current soil temperature and moisture of a polygon.
+    pub fn soil(&self, id: &str) -> Result<Soil, String> {
+        self.get(&format!(
+            "{}/soil?polyid={}&appid={}",
+            self.url, id, self.api_key
+        ))
+    }
+
+    /// Gets the current weather of a polygon.
+    pub fn weather(&self, id: &str) -> Result<AgroWeather, String> {
+        self.get(&format!(
+            "{}/weather?polyid={}&appid={}",
+            self.url, id, self.api_key
+        ))
+    }
+
+    /// Searches satellite images of a polygon.
+    /// #### Parameters
+    /// - `id`: polygon ID
+    /// - `start`, `end`: time range, unix, UTC
+    pub fn images(&self, id: &str, start: i64, end: i64) -> Result<Vec<SatelliteImage>, String> {
+        self.get(&format!(
+            "{}/image/search?start={}&end={}&polyid={}&appid={}",
+            self.url, start, end, id, self.api_key
+        ))
+    }
+
+    /// Fetches index statistics from a URL of `SatelliteImage::stats`.
+    pub fn stats(&self, url: &str) -> Result<IndexStats, String> {
+        self.get(url)
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        parse(request::<()>(Method::GET, url, None)?)
+    }
+}
@@ -1,7 +1,9 @@
-use serde::Deserialize;
+use crate::Units;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /// Location coordinates
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Coord {
     /// geo location, longitude
     pub lon: f64,
@@ -10,7 +12,7 @@ pub struct Coord {
 }
 
 /// Weather condition description
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Weather {
     /// Weather condition id
     pub id: u64,
@@ -20,10 +22,13 @@ pub struct Weather {
     pub description: String,
     /// Weather icon id
     pub icon: String,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 /// Detailed weather report
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Main {
     /// Temperature. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit.
     pub temp: f64,
@@ -46,28 +51,38 @@ pub struct Main {
     pub sea_level: Option<f64>,
     /// Atmospheric pressure on the ground level, hPa
     pub grnd_level: Option<f64>,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 /// Detailed wind report
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Wind {
     /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour.
     pub speed: f64,
-    /// Wind direction, degrees (meteorological)
-    pub deg: f64,
+    /// Wind direction, degrees (meteorological).
+    /// Missing if the station does not report it.
+    pub deg: Option<f64>,
     /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
     pub gust: Option<f64>,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 /// Detailed clouds report
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Clouds {
     /// Cloudiness, %
     pub all: f64,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 /// Rain or snow volume report
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Volume {
     /// Volume for the last 1 hour, mm
     #[serde(rename = "1h")]
@@ -75,10 +90,13 @@ pub struct Volume {
     /// Volume for the last 3 hours, mm
     #[serde(rename = "3h")]
     pub h3: Option<f64>,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 /// Additional information
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Sys {
     /// Internal parameter
     #[serde(rename = "type")]
@@ -87,15 +105,18 @@ pub struct Sys {
     pub id: Option<u64>,
     /// Internal parameter
     pub message: Option<f64>,
-    /// Country code (GB, JP etc.)
-    pub country: String,
-    /// Sunrise time, unix, UTC
-    pub sunrise: i64,
-    /// Sunset time, unix, UTC
-    pub sunset: i64,
+    /// Country code (GB, JP etc.). Missing for coordinates on the ocean.
+    pub country: Option<String>,
+    /// Sunrise time, unix, UTC. Missing during polar day and polar night.
+    pub sunrise: Option<i64>,
+    /// Sunset time, unix, UTC. Missing during polar day and polar night.
+    pub sunset: Option<i64>,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 /// current weather report in a nested struct
 pub struct CurrentWeather {
     /// report origin coordinates
@@ -106,8 +127,8 @@ pub struct CurrentWeather {
     pub base: String,
     /// detailed weather report
     pub main: Main,
-    /// Visibility, meter
-    pub visibility: u64,
+    /// Visibility, meter. Missing if the station does not report it.
+    pub visibility: Option<u64>,
     /// detailed wind report
     pub wind: Wind,
     /// detailed clouds report
@@ -128,4 +149,13 @@ pub struct CurrentWeather {
     pub name: String,
     /// Internal parameter
     pub cod: u64,
+    /// Unit system of temperatures and wind speeds (taken from the request by the functions
+    /// of this crate, `Units::Standard` if the report was parsed otherwise). Serialized as
+    /// `"_units"` unless it is `Units::Standard`, so that a serialized report can be parsed
+    /// again without losing it.
+    #[serde(rename = "_units", default, skip_serializing_if = "Units::is_standard")]
+    pub units: Units,
+    /// Fields unknown to this crate (e.g. recently added by *OpenWeatherMap*)
+    #[serde(flatten, default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
@@ -0,0 +1,237 @@
+//! Offline calculation of sun and moon events for any location
+//! (after the [sunrise equation](https://en.wikipedia.org/wiki/Sunrise_equation) and
+//! the [position of the sun](https://aa.quae.nl/en/reken/zonpositie.html)).
+//!
+//! All times are unix times (UTC) like in the weather reports.
+//! Results are accurate to about a minute for sun events and to about half a day for
+//! moon phases.
+
+use crate::{Coord, CurrentWeather};
+
+// Julian day of the unix epoch
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+// Julian day of the epoch J2000.0
+const J2000: f64 = 2451545.0;
+// obliquity of the ecliptic, degrees
+const OBLIQUITY: f64 = 23.4397;
+// sun elevation at sunrise and sunset including refraction and the radius of the sun, degrees
+const SUNRISE_ELEVATION: f64 = -0.833;
+// Julian day of a new moon (2000-01-06 18:14 UTC)
+const NEW_MOON_JD: f64 = 2451550.26;
+/// Mean length of a lunation, days
+pub const SYNODIC_MONTH: f64 = 29.530588853;
+
+fn julian_day(unix: i64) -> f64 {
+    unix as f64 / 86400.0 + UNIX_EPOCH_JD
+}
+
+fn unix_time(julian_day: f64) -> i64 {
+    ((julian_day - UNIX_EPOCH_JD) * 86400.0).round() as i64
+}
+
+fn sin(deg: f64) -> f64 {
+    deg.to_radians().sin()
+}
+
+fn cos(deg: f64) -> f64 {
+    deg.to_radians().cos()
+}
+
+/// Mean anomaly and ecliptic longitude of the sun `d` days after J2000.0, degrees.
+fn ecliptic(d: f64) -> (f64, f64) {
+    let m = (357.5291 + 0.98560028 * d).rem_euclid(360.0);
+    let c = 1.9148 * sin(m) + 0.0200 * sin(2.0 * m) + 0.0003 * sin(3.0 * m);
+    (m, (m + c + 180.0 + 102.9372).rem_euclid(360.0))
+}
+
+/// Declination of the sun at ecliptic longitude `lambda`, degrees.
+fn declination(lambda: f64) -> f64 {
+    (sin(lambda) * sin(OBLIQUITY)).asin().to_degrees()
+}
+
+/// Twilight defined by the elevation of the sun below the horizon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Twilight {
+    /// Sun 6° below the horizon
+    Civil,
+    /// Sun 12° below the horizon
+    Nautical,
+    /// Sun 18° below the horizon
+    Astronomical,
+}
+
+impl Twilight {
+    /// Elevation of the sun at the begin of dawn and the end of dusk, degrees.
+    pub fn elevation(&self) -> f64 {
+        match self {
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
+
+/// Solar day at a location
+struct SolarDay {
+    transit: f64,
+    declination: f64,
+    lat: f64,
+}
+
+impl SolarDay {
+    /// Solar day whose noon is nearest to `unix`.
+    fn new(coord: &Coord, unix: i64) -> SolarDay {
+        let n = (julian_day(unix) - J2000 - 0.0008 + coord.lon / 360.0).round();
+        let mean_noon = n - coord.lon / 360.0;
+        let (m, lambda) = ecliptic(mean_noon);
+        SolarDay {
+            transit: J2000 + mean_noon + 0.0053 * sin(m) - 0.0069 * sin(2.0 * lambda),
+            declination: declination(lambda),
+            lat: coord.lat,
+        }
+    }
+
+    /// Times when the sun passes `elevation` rising and setting.
+    /// `None` if the sun stays above or below it all day.
+    fn crossing(&self, elevation: f64) -> Option<(i64, i64)> {
+        let cos_hour_angle = (sin(elevation) - sin(self.lat) * sin(self.declination))
+            / (cos(self.lat) * cos(self.declination));
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let offset = cos_hour_angle.acos().to_degrees() / 360.0;
+        Some((
+            unix_time(self.transit - offset),
+            unix_time(self.transit + offset),
+        ))
+    }
+}
+
+/// Sun events of a solar day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    /// Time when the sun is highest
+    pub solar_noon: i64,
+    /// Sunrise, `None` during polar day or polar night
+    pub sunrise: Option<i64>,
+    /// Sunset, `None` during polar day or polar night
+    pub sunset: Option<i64>,
+}
+
+/// Sun events of the solar day at `coord` whose noon is nearest to `unix`.
+pub fn sun_times(coord: &Coord, unix: i64) -> SunTimes {
+    let day = SolarDay::new(coord, unix);
+    let sun = day.crossing(SUNRISE_ELEVATION);
+    SunTimes {
+        solar_noon: unix_time(day.transit),
+        sunrise: sun.map(|(rise, _)| rise),
+        sunset: sun.map(|(_, set)| set),
+    }
+}
+
+/// Begin of dawn and end of dusk of the solar day at `coord` whose noon is nearest to `unix`.
+/// #### Return value
+/// - `None` if the sun does not pass the elevation of `twilight` on that day
+pub fn twilight(coord: &Coord, unix: i64, twilight: Twilight) -> Option<(i64, i64)> {
+    SolarDay::new(coord, unix).crossing(twilight.elevation())
+}
+
+/// Position of the sun in the sky
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunPosition {
+    /// Angle above the horizon (negative below), degrees
+    pub elevation: f64,
+    /// Direction clockwise from north, degrees
+    pub azimuth: f64,
+}
+
+/// Position of the sun at `coord` and `unix` time (without refraction).
+pub fn sun_position(coord: &Coord, unix: i64) -> SunPosition {
+    let d = julian_day(unix) - J2000;
+    let (_, lambda) = ecliptic(d);
+    let delta = declination(lambda);
+    let alpha = (sin(lambda) * cos(OBLIQUITY))
+        .atan2(cos(lambda))
+        .to_degrees();
+    let sidereal = 280.1470 + 360.9856235 * d + coord.lon;
+    let hour_angle = sidereal - alpha;
+    let elevation = (sin(coord.lat) * sin(delta) + cos(coord.lat) * cos(delta) * cos(hour_angle))
+        .asin()
+        .to_degrees();
+    // measured from south westwards
+    let azimuth = sin(hour_angle)
+        .atan2(cos(hour_angle) * sin(coord.lat) - delta.to_radians().tan() * cos(coord.lat))
+        .to_degrees();
+    SunPosition {
+        elevation,
+        azimuth: (azimuth + 180.0).rem_euclid(360.0),
+    }
+}
+
+/// Principal and intermediate phases of the moon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoonPhase {
+    /// Moon not visible
+    NewMoon,
+    /// Less than half illuminated, growing
+    WaxingCrescent,
+    /// Right half illuminated (on the northern hemisphere)
+    FirstQuarter,
+    /// More than half illuminated, growing
+    WaxingGibbous,
+    /// Fully illuminated
+    FullMoon,
+    /// More than half illuminated, shrinking
+    WaningGibbous,
+    /// Left half illuminated (on the northern hemisphere)
+    LastQuarter,
+    /// Less than half illuminated, shrinking
+    WaningCrescent,
+}
+
+/// State of the moon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moon {
+    /// Days since the last new moon
+    pub age: f64,
+    /// Phase (each spanning an eighth of the lunation centered on the principal phases)
+    pub phase: MoonPhase,
+    /// Illuminated fraction of the disk from `0.0` to `1.0`
+    pub illumination: f64,
+}
+
+/// State of the moon at `unix` time after its mean lunation.
+pub fn moon(unix: i64) -> Moon {
+    use MoonPhase::*;
+    let fraction = ((julian_day(unix) - NEW_MOON_JD) / SYNODIC_MONTH).rem_euclid(1.0);
+    let phase = [
+        NewMoon,
+        WaxingCrescent,
+        FirstQuarter,
+        WaxingGibbous,
+        FullMoon,
+        WaningGibbous,
+        LastQuarter,
+        WaningCrescent,
+    ][(fraction * 8.0).round() as usize % 8];
+    Moon {
+        age: fraction * SYNODIC_MONTH,
+        phase,
+        illumination: (1.0 - (2.0 * std::f64::consts::PI * fraction).cos()) / 2.0,
+    }
+}
+
+impl CurrentWeather {
+    /// Sun events of the day of `dt` calculated from `coord`.
+    pub fn sun_times(&self) -> SunTimes {
+        sun_times(&self.coord, self.dt)
+    }
+
+    /// Sets `sys.sunrise` and `sys.sunset` to the calculated times if the report misses them
+    /// (they stay `None` during polar day or polar night).
+    pub fn fill_sun_times(&mut self) {
+        let times = self.sun_times();
+        self.sys.sunrise = self.sys.sunrise.or(times.sunrise);
+        self.sys.sunset = self.sys.sunset.or(times.sunset);
+    }
+}
@@ -0,0 +1,117 @@
+//! Parse files from [bulk.openweathermap.org](https://bulk.openweathermap.org/sample/)
+//! like the city list `current.city.list.min.json.gz` or current weather dumps.
+//!
+//! ```no_run
+//! use std::fs::File;
+//!
+//! let file = File::open("current.city.list.min.json.gz").unwrap();
+//! for city in openweathermap::bulk::cities_gz(file).unwrap() {
+//!     println!("{}: {},{}", city.id, city.name, city.country);
+//! }
+//! ```
+
+use crate::{Coord, CurrentWeather};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Lines, Read};
+
+/// City population statistics
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CityStat {
+    /// Internal parameter
+    pub level: Option<f64>,
+    /// Population
+    pub population: Option<u64>,
+}
+
+/// City name in another language
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CityLang {
+    /// Language code
+    pub lang: Option<String>,
+    /// Name in that language
+    pub name: Option<String>,
+}
+
+/// City entry of the city list
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct City {
+    /// City ID
+    pub id: u64,
+    /// City name
+    pub name: String,
+    /// State code (US only)
+    #[serde(default)]
+    pub state: String,
+    /// Country code (GB, JP etc.)
+    pub country: String,
+    /// City geo location
+    pub coord: Coord,
+    /// Population statistics
+    pub stat: Option<CityStat>,
+    /// Names in other languages
+    #[serde(default)]
+    pub langs: Vec<CityLang>,
+}
+
+impl City {
+    /// City ID as location for `init()` or `weather()`.
+    pub fn location(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+/// Reads a city list given as JSON array.
+/// #### Parameters
+/// - `reader`: source of the uncompressed city list (e.g. a `File`)
+/// #### Return value
+/// - ⇒ `Ok(Vec<City>)`: all cities of the list
+/// - ⇒ `Err(String)`: Error message about any occured io or json issue
+pub fn cities<R: Read>(reader: R) -> Result<Vec<City>, String> {
+    serde_json::from_reader(BufReader::new(reader)).map_err(|e| e.to_string())
+}
+
+/// Reads a gzip compressed city list (e.g. `current.city.list.min.json.gz`).
+/// See also `cities()`.
+pub fn cities_gz<R: Read>(reader: R) -> Result<Vec<City>, String> {
+    cities(GzDecoder::new(reader))
+}
+
+/// Iterator over the current weather reports of a newline-delimited JSON dump.
+/// Empty lines are skipped and every other line yields one report.
+pub struct WeatherReader<R: Read> {
+    lines: Lines<BufReader<R>>,
+}
+
+impl<R: Read> Iterator for WeatherReader<R> {
+    type Item = Result<CurrentWeather, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            return match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => Some(serde_json::from_str(&line).map_err(|e| e.to_string())),
+                Err(e) => Some(Err(e.to_string())),
+            };
+        }
+    }
+}
+
+/// Reads current weather reports from a newline-delimited JSON dump.
+/// #### Parameters
+/// - `reader`: source of the uncompressed dump (e.g. a `File`)
+/// #### Return value
+/// - an iterator which yields per line:
+///     - ⇒ `Ok(CurrentWeather)`: weather information in a nested struct called `CurrentWeather`
+///     - ⇒ `Err(String)`: Error message about any occured io or json issue
+pub fn weather<R: Read>(reader: R) -> WeatherReader<R> {
+    WeatherReader {
+        lines: BufReader::new(reader).lines(),
+    }
+}
+
+/// Reads current weather reports from a gzip compressed newline-delimited JSON dump.
+/// See also `weather()`.
+pub fn weather_gz<R: Read>(reader: R) -> WeatherReader<GzDecoder<R>> {
+    weather(GzDecoder::new(reader))
+}
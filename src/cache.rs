@@ -0,0 +1,388 @@
+//! Caches of weather responses to avoid repeated requests for data *OpenWeatherMap* has
+//! not updated yet and to survive being offline.
+//!
+//! Once a cache is enabled, all JSON and XML requests of this crate (including the polling
+//! thread of `init()`) answer from it as long as the cached response is younger than its TTL.
+//!
+//! ```no_run
+//! use openweathermap::cache::{self, Cache};
+//!
+//! let cache = Cache::default();
+//! cache::enable(cache.clone());
+//! openweathermap::blocking::weather("Berlin,DE", "metric", "en", "<APIKEY>").unwrap();
+//! openweathermap::blocking::weather("Berlin,DE", "metric", "en", "<APIKEY>").unwrap();
+//! assert_eq!(cache.hits(), 1);
+//! ```
+//!
+//! A `DiskCache` additionally keeps the latest response of every request in a directory so
+//! that weather can be shown after a restart without network. Such outdated data is only
+//! served by `weather_cached()`, which tells its age, and only if *OpenWeatherMap* cannot be
+//! reached or fails with a server error (`5xx`).
+
+use reqwest::Url;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Identification of a cached response
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// Host and path of the requested API (e.g. `"api.openweathermap.org/data/2.5/weather"`)
+    pub endpoint: String,
+    /// Query parameters selecting the location (e.g. `"q=Berlin,DE"` or `"lat=52.5&lon=13.4"`)
+    pub location: String,
+    /// Requested unit system
+    pub units: String,
+    /// Requested language
+    pub lang: String,
+}
+
+impl Key {
+    /// Key of a request URL. The API key is ignored, so responses are shared between keys.
+    pub fn from_url(url: &str) -> Result<Key, String> {
+        let url = Url::parse(url).map_err(|e| e.to_string())?;
+        let mut key = Key {
+            endpoint: format!(
+                "{}{}{}",
+                url.host_str().unwrap_or_default(),
+                url.port().map(|p| format!(":{}", p)).unwrap_or_default(),
+                url.path()
+            ),
+            location: String::new(),
+            units: String::new(),
+            lang: String::new(),
+        };
+        let mut location = Vec::new();
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "units" => key.units = value.into_owned(),
+                "lang" => key.lang = value.into_owned(),
+                "appid" => (),
+                _ => location.push(format!("{}={}", name, value)),
+            }
+        }
+        location.sort();
+        key.location = location.join("&");
+        Ok(key)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<Key, (Instant, String)>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Response cache which can be shared between threads (clones share the same entries)
+#[derive(Clone)]
+pub struct Cache {
+    ttl: Duration,
+    max_entries: usize,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for Cache {
+    /// Cache keeping up to 1000 responses for 10 minutes (the interval *OpenWeatherMap*
+    /// updates current weather data in).
+    fn default() -> Self {
+        Cache::new(Duration::from_secs(600), 1000)
+    }
+}
+
+impl Cache {
+    /// Creates an empty cache.
+    /// #### Parameters
+    /// - `ttl`: time a response is served from the cache
+    /// - `max_entries`: number of responses kept (the oldest one is dropped first)
+    pub fn new(ttl: Duration, max_entries: usize) -> Cache {
+        Cache {
+            ttl,
+            max_entries,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Response cached for `key` if it is not older than the TTL.
+    pub fn get(&self, key: &Key) -> Option<String> {
+        self.lookup(key).map(|(response, _)| response)
+    }
+
+    /// Response cached for `key` together with its age if it is not older than the TTL.
+    fn lookup(&self, key: &Key) -> Option<(String, Duration)> {
+        let mut state = self.state();
+        let found = match state.entries.get(key) {
+            Some((time, response)) if time.elapsed() < self.ttl => {
+                Some((response.clone(), time.elapsed()))
+            }
+            _ => None,
+        };
+        match found {
+            Some(_) => state.hits += 1,
+            None => state.misses += 1,
+        }
+        found
+    }
+
+    /// Caches `response` for `key`.
+    pub fn insert(&self, key: Key, response: String) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut state = self.state();
+        let ttl = self.ttl;
+        state.entries.retain(|_, (time, _)| time.elapsed() < ttl);
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (time, _))| *time)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(key, (Instant::now(), response));
+    }
+
+    /// Number of requests answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.state().hits
+    }
+
+    /// Number of requests which had to be sent to *OpenWeatherMap*.
+    pub fn misses(&self) -> u64 {
+        self.state().misses
+    }
+
+    /// Number of cached responses (including expired ones not dropped yet).
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Whether no responses are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all cached responses (the counters are kept).
+    pub fn clear(&self) {
+        self.state().entries.clear();
+    }
+}
+
+/// Longest file name of a `DiskCache` entry without extension, bytes
+const MAX_NAME: usize = 200;
+
+/// 64 bit FNV-1a hash (stable across releases unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Cache keeping the latest response of every request as a file in a directory, so that
+/// it survives restarts and can be served while *OpenWeatherMap* is not reachable
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Creates a cache within `dir` (which is created if missing) or opens an existing one.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<DiskCache, String> {
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        Ok(DiskCache {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Path of the file storing the response for `key`. Long names are cut and end with a
+    /// hash of the full name instead, so that they fit into the file name limit of 255 bytes.
+    fn path(&self, key: &Key) -> PathBuf {
+        let mut name = String::new();
+        for part in [&key.endpoint, &key.location, &key.units, &key.lang] {
+            for b in part.bytes() {
+                match b.is_ascii_alphanumeric() || b == b'.' || b == b'-' {
+                    true => name.push(b as char),
+                    false => name.push_str(&format!("_{:02x}", b)),
+                }
+            }
+            name.push('~');
+        }
+        if name.len() > MAX_NAME {
+            let hash = fnv1a(name.as_bytes());
+            name.truncate(MAX_NAME - 17);
+            name += &format!("~{:016x}", hash);
+        }
+        self.dir.join(name + ".json")
+    }
+
+    /// Latest response stored for `key` together with its age (regardless how old it is).
+    pub fn get(&self, key: &Key) -> Option<(String, Duration)> {
+        let path = self.path(key);
+        let response = fs::read_to_string(&path).ok()?;
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        Some((response, age))
+    }
+
+    /// Stores `response` for `key` replacing any older one.
+    pub fn insert(&self, key: &Key, response: &str) -> Result<(), String> {
+        let path = self.path(key);
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, response).map_err(|e| e.to_string())?;
+        fs::rename(&temp, &path).map_err(|e| e.to_string())
+    }
+
+    /// Removes all stored responses.
+    pub fn clear(&self) -> Result<(), String> {
+        for entry in fs::read_dir(&self.dir).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                fs::remove_file(path).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Response together with the information where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cached<T> {
+    /// The response
+    pub value: T,
+    /// Time since the response was received from *OpenWeatherMap* (zero if just requested)
+    pub age: Duration,
+    /// Whether *OpenWeatherMap* was unavailable and an outdated response from the `DiskCache`
+    /// was served
+    pub stale: bool,
+    /// Key of the enabled `KeyPool` the response was requested with (`None` if it was served
+    /// from a cache or no pool is enabled, see module `keys`)
+    pub api_key: Option<String>,
+}
+
+impl<T> Cached<T> {
+    /// Converts the value keeping age and staleness.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Cached<U> {
+        Cached {
+            value: f(self.value),
+            age: self.age,
+            stale: self.stale,
+            api_key: self.api_key,
+        }
+    }
+}
+
+/// Failure of a request answered by `fetch()`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Failure<E> {
+    /// *OpenWeatherMap* could not be reached or failed with a server error (`5xx`), so an
+    /// outdated response may be served instead
+    Unavailable(E),
+    /// The request was refused (e.g. `401 Unauthorized`, `404 Not Found` or by the rate limiter)
+    Refused(E),
+}
+
+/// Answers a request for `url` from `memory` or by calling `fetch`. Successful responses are
+/// stored in both caches. If `serve_stale` is set and *OpenWeatherMap* is unavailable, the
+/// outdated response of `disk` is served.
+pub(crate) fn fetch<E, F>(
+    memory: Option<&Cache>,
+    disk: Option<&DiskCache>,
+    url: &str,
+    serve_stale: bool,
+    fetch: F,
+) -> Result<Cached<String>, E>
+where
+    F: FnOnce() -> Result<String, Failure<E>>,
+{
+    let fresh = |value| Cached {
+        value,
+        age: Duration::ZERO,
+        stale: false,
+        api_key: None,
+    };
+    let key = match Key::from_url(url) {
+        Ok(key) => key,
+        Err(_) => {
+            return fetch().map(fresh).map_err(|e| match e {
+                Failure::Unavailable(e) | Failure::Refused(e) => e,
+            })
+        }
+    };
+    if let Some((value, age)) = memory.and_then(|cache| cache.lookup(&key)) {
+        return Ok(Cached {
+            value,
+            age,
+            stale: false,
+            api_key: None,
+        });
+    }
+    match fetch() {
+        Ok(response) => {
+            if let Some(disk) = disk {
+                // a full disk must not break fetching weather
+                disk.insert(&key, &response).unwrap_or(());
+            }
+            if let Some(memory) = memory {
+                memory.insert(key, response.clone());
+            }
+            Ok(fresh(response))
+        }
+        Err(Failure::Unavailable(e)) => {
+            match disk.filter(|_| serve_stale).and_then(|disk| disk.get(&key)) {
+                Some((value, age)) => Ok(Cached {
+                    value,
+                    age,
+                    stale: true,
+                    api_key: None,
+                }),
+                None => Err(e),
+            }
+        }
+        Err(Failure::Refused(e)) => Err(e),
+    }
+}
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+static DISK_CACHE: Mutex<Option<DiskCache>> = Mutex::new(None);
+
+/// Answers all following requests of this crate from `cache` where possible.
+pub fn enable(cache: Cache) {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(cache);
+}
+
+/// Sends all following requests to *OpenWeatherMap* again.
+pub fn disable() {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Cache currently enabled.
+pub fn current() -> Option<Cache> {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Stores all following responses in `cache` and lets `weather_cached()` serve them from
+/// there while *OpenWeatherMap* is unavailable.
+pub fn enable_disk(cache: DiskCache) {
+    *DISK_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(cache);
+}
+
+/// Stops using the disk cache (its files are kept).
+pub fn disable_disk() {
+    *DISK_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Disk cache currently enabled.
+pub fn current_disk() -> Option<DiskCache> {
+    DISK_CACHE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
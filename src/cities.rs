@@ -0,0 +1,237 @@
+//! Offline city index with fuzzy name lookup and nearest city search
+//! (requires feature `city-index`).
+//!
+//! The index is built from the city list of
+//! [bulk.openweathermap.org](https://bulk.openweathermap.org/sample/current.city.list.min.json.gz)
+//! so that misspelled names can be resolved to a city ID before calling the API:
+//!
+//! ```no_run
+//! use openweathermap::cities::CityIndex;
+//!
+//! let index = CityIndex::embedded();
+//! let munich = index.search("Munchen,DE", 1)[0];
+//! let weather = openweathermap::blocking::weather(&munich.location(), "metric", "de", "<APIKEY>");
+//! ```
+//!
+//! `CityIndex::embedded()` reads the compact index `data/cities.tsv.gz` which is compiled
+//! into the crate. It is generated from the full city list by
+//! `cargo run --example city_index --features city-index -- current.city.list.min.json.gz`.
+//! Use `CityIndex::from_gz()` to index a more recent city list at runtime.
+
+use crate::bulk::{cities_gz, City, CityStat};
+use crate::Coord;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::OnceLock;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Mean earth radius, km
+const EARTH_RADIUS: f64 = 6371.0;
+
+/// Compact index compiled into the crate (see `CityIndex::embedded()`)
+static EMBEDDED: &[u8] = include_bytes!("../data/cities.tsv.gz");
+
+/// Searchable collection of cities.
+pub struct CityIndex {
+    cities: Vec<City>,
+    // normalized names in the same order as `cities`
+    names: Vec<String>,
+}
+
+/// Lowercases `name` and removes diacritics (e.g. `"München"` ⇒ `"munchen"`).
+pub fn normalize(name: &str) -> String {
+    name.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'ß' => "ss".to_string(),
+            'æ' => "ae".to_string(),
+            'ø' => "o".to_string(),
+            'ł' => "l".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Number of single character edits which turn `a` into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = (current + 1)
+                .min(row[j] + 1)
+                .min(previous + if ca == *cb { 0 } else { 1 });
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Great circle distance between two coordinates, km.
+pub fn distance_km(a: &Coord, b: &Coord) -> f64 {
+    let (lat1, lat2) = (a.lat.to_radians(), b.lat.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.lon - a.lon).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+impl CityIndex {
+    /// Index of the given cities.
+    pub fn new(cities: Vec<City>) -> CityIndex {
+        let names = cities.iter().map(|c| normalize(&c.name)).collect();
+        CityIndex { cities, names }
+    }
+
+    /// Index of a gzip compressed city list (e.g. `current.city.list.min.json.gz`).
+    pub fn from_gz<R: Read>(reader: R) -> Result<CityIndex, String> {
+        Ok(CityIndex::new(cities_gz(reader)?))
+    }
+
+    /// Index compiled into the crate (see module documentation how it is generated).
+    /// It is decompressed on the first call only.
+    pub fn embedded() -> &'static CityIndex {
+        static INDEX: OnceLock<CityIndex> = OnceLock::new();
+        INDEX.get_or_init(|| {
+            CityIndex::from_compact(EMBEDDED).expect("embedded city index is valid")
+        })
+    }
+
+    /// Index of a gzip compressed compact index as written by `write_compact()`.
+    pub fn from_compact<R: Read>(reader: R) -> Result<CityIndex, String> {
+        let mut cities = Vec::new();
+        for line in BufReader::new(GzDecoder::new(reader)).lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            let invalid = || format!("invalid city index line '{}'", line);
+            if fields.len() != 7 {
+                return Err(invalid());
+            }
+            let population = match fields[6] {
+                "" => None,
+                p => Some(p.parse().map_err(|_| invalid())?),
+            };
+            cities.push(City {
+                id: fields[0].parse().map_err(|_| invalid())?,
+                name: fields[1].to_string(),
+                state: fields[2].to_string(),
+                country: fields[3].to_string(),
+                coord: Coord {
+                    lat: fields[4].parse().map_err(|_| invalid())?,
+                    lon: fields[5].parse().map_err(|_| invalid())?,
+                },
+                stat: population.map(|population| CityStat {
+                    level: None,
+                    population: Some(population),
+                }),
+                langs: Vec::new(),
+            });
+        }
+        Ok(CityIndex::new(cities))
+    }
+
+    /// Writes the cities as gzip compressed compact index (one line of tab separated ID,
+    /// name, state, country, latitude, longitude and population per city). Names in other
+    /// languages are not kept.
+    pub fn write_compact<W: Write>(&self, writer: W) -> Result<(), String> {
+        let mut gz = GzEncoder::new(writer, Compression::best());
+        for city in &self.cities {
+            let population = city.stat.as_ref().and_then(|s| s.population);
+            writeln!(
+                gz,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                city.id,
+                city.name.replace(['\t', '\n'], " "),
+                city.state,
+                city.country,
+                city.coord.lat,
+                city.coord.lon,
+                population.map(|p| p.to_string()).unwrap_or_default()
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        gz.finish().map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    /// All indexed cities.
+    pub fn cities(&self) -> &[City] {
+        &self.cities
+    }
+
+    /// City with the given ID.
+    pub fn get(&self, id: u64) -> Option<&City> {
+        self.cities.iter().find(|c| c.id == id)
+    }
+
+    /// Searches cities by name ignoring case and diacritics while tolerating typos.
+    /// #### Parameters
+    /// - `query`: city name which may be followed by comma separated state code and/or country
+    ///   code (e.g. `"Munchen,DE"` or `"Springfield,IL,US"`)
+    /// - `limit`: maximum number of results
+    /// #### Return value
+    /// - cities ordered by relevance: exact matches, then names starting with the query,
+    ///   then names with a few typos (up to one edit per four characters)
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&City> {
+        let mut parts = query.split(',').map(str::trim);
+        let name = normalize(parts.next().unwrap_or(""));
+        let codes: Vec<String> = parts.map(str::to_uppercase).collect();
+        let (state, country) = match codes.as_slice() {
+            [] => (None, None),
+            [country] => (None, Some(country)),
+            [state, country, ..] => (Some(state), Some(country)),
+        };
+        let tolerance = (name.chars().count() / 4).max(1);
+        let mut found: Vec<(usize, usize, &City)> = self
+            .cities
+            .iter()
+            .zip(self.names.iter())
+            .filter(|(city, _)| {
+                state.is_none_or(|s| city.state == *s) && country.is_none_or(|c| city.country == *c)
+            })
+            .filter_map(|(city, n)| {
+                if *n == name {
+                    Some((0, 0, city))
+                } else if n.starts_with(&name) {
+                    Some((1, n.len() - name.len(), city))
+                } else {
+                    let d = distance(&name, n);
+                    if d <= tolerance {
+                        Some((2, d, city))
+                    } else {
+                        None
+                    }
+                }
+            })
+            .collect();
+        // prefer better matches and bigger cities
+        found.sort_by_key(|(rank, d, city)| {
+            let population = city.stat.as_ref().and_then(|s| s.population).unwrap_or(0);
+            (*rank, *d, std::cmp::Reverse(population))
+        });
+        found.into_iter().take(limit).map(|(_, _, c)| c).collect()
+    }
+
+    /// City which is nearest to the given coordinate.
+    pub fn nearest(&self, coord: &Coord) -> Option<&City> {
+        self.nearest_n(coord, 1).into_iter().next().map(|(c, _)| c)
+    }
+
+    /// Up to `n` cities ordered by their distance (km) to the given coordinate.
+    pub fn nearest_n(&self, coord: &Coord, n: usize) -> Vec<(&City, f64)> {
+        let mut found: Vec<(&City, f64)> = self
+            .cities
+            .iter()
+            .map(|c| (c, distance_km(coord, &c.coord)))
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found.truncate(n);
+        found
+    }
+}
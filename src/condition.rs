@@ -0,0 +1,405 @@
+use crate::Weather;
+
+/// Group of weather conditions (first digit of the condition id)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Group {
+    /// 2xx
+    Thunderstorm,
+    /// 3xx
+    Drizzle,
+    /// 5xx
+    Rain,
+    /// 6xx
+    Snow,
+    /// 7xx: mist, smoke, haze, dust, fog, sand, ash, squalls and tornado
+    Atmosphere,
+    /// 800
+    Clear,
+    /// 80x
+    Clouds,
+    /// any id not covered by the documented table
+    Unknown,
+}
+
+/// Intensity of a weather condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Intensity {
+    /// clear sky, few clouds or an atmospheric phenomenon without intensity
+    None,
+    /// light or ragged
+    Light,
+    /// moderate
+    Moderate,
+    /// heavy
+    Heavy,
+    /// extreme
+    Extreme,
+}
+
+/// Weather condition as documented in the
+/// [weather condition table](https://openweathermap.org/weather-conditions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Condition {
+    /// 200
+    ThunderstormWithLightRain,
+    /// 201
+    ThunderstormWithRain,
+    /// 202
+    ThunderstormWithHeavyRain,
+    /// 210
+    LightThunderstorm,
+    /// 211
+    Thunderstorm,
+    /// 212
+    HeavyThunderstorm,
+    /// 221
+    RaggedThunderstorm,
+    /// 230
+    ThunderstormWithLightDrizzle,
+    /// 231
+    ThunderstormWithDrizzle,
+    /// 232
+    ThunderstormWithHeavyDrizzle,
+    /// 300
+    LightDrizzle,
+    /// 301
+    Drizzle,
+    /// 302
+    HeavyDrizzle,
+    /// 310
+    LightDrizzleRain,
+    /// 311
+    DrizzleRain,
+    /// 312
+    HeavyDrizzleRain,
+    /// 313
+    ShowerRainAndDrizzle,
+    /// 314
+    HeavyShowerRainAndDrizzle,
+    /// 321
+    ShowerDrizzle,
+    /// 500
+    LightRain,
+    /// 501
+    ModerateRain,
+    /// 502
+    HeavyRain,
+    /// 503
+    VeryHeavyRain,
+    /// 504
+    ExtremeRain,
+    /// 511
+    FreezingRain,
+    /// 520
+    LightShowerRain,
+    /// 521
+    ShowerRain,
+    /// 522
+    HeavyShowerRain,
+    /// 531
+    RaggedShowerRain,
+    /// 600
+    LightSnow,
+    /// 601
+    Snow,
+    /// 602
+    HeavySnow,
+    /// 611
+    Sleet,
+    /// 612
+    LightShowerSleet,
+    /// 613
+    ShowerSleet,
+    /// 615
+    LightRainAndSnow,
+    /// 616
+    RainAndSnow,
+    /// 620
+    LightShowerSnow,
+    /// 621
+    ShowerSnow,
+    /// 622
+    HeavyShowerSnow,
+    /// 701
+    Mist,
+    /// 711
+    Smoke,
+    /// 721
+    Haze,
+    /// 731
+    SandDustWhirls,
+    /// 741
+    Fog,
+    /// 751
+    Sand,
+    /// 761
+    Dust,
+    /// 762
+    VolcanicAsh,
+    /// 771
+    Squalls,
+    /// 781
+    Tornado,
+    /// 800
+    ClearSky,
+    /// 801: 11-25%
+    FewClouds,
+    /// 802: 25-50%
+    ScatteredClouds,
+    /// 803: 51-84%
+    BrokenClouds,
+    /// 804: 85-100%
+    OvercastClouds,
+    /// id not covered by the documented table
+    Unknown(u64),
+}
+
+use Condition::*;
+
+/// All documented conditions with their id and English description
+const TABLE: &[(Condition, u64, &str)] = &[
+    (
+        ThunderstormWithLightRain,
+        200,
+        "thunderstorm with light rain",
+    ),
+    (ThunderstormWithRain, 201, "thunderstorm with rain"),
+    (
+        ThunderstormWithHeavyRain,
+        202,
+        "thunderstorm with heavy rain",
+    ),
+    (LightThunderstorm, 210, "light thunderstorm"),
+    (Thunderstorm, 211, "thunderstorm"),
+    (HeavyThunderstorm, 212, "heavy thunderstorm"),
+    (RaggedThunderstorm, 221, "ragged thunderstorm"),
+    (
+        ThunderstormWithLightDrizzle,
+        230,
+        "thunderstorm with light drizzle",
+    ),
+    (ThunderstormWithDrizzle, 231, "thunderstorm with drizzle"),
+    (
+        ThunderstormWithHeavyDrizzle,
+        232,
+        "thunderstorm with heavy drizzle",
+    ),
+    (LightDrizzle, 300, "light intensity drizzle"),
+    (Drizzle, 301, "drizzle"),
+    (HeavyDrizzle, 302, "heavy intensity drizzle"),
+    (LightDrizzleRain, 310, "light intensity drizzle rain"),
+    (DrizzleRain, 311, "drizzle rain"),
+    (HeavyDrizzleRain, 312, "heavy intensity drizzle rain"),
+    (ShowerRainAndDrizzle, 313, "shower rain and drizzle"),
+    (
+        HeavyShowerRainAndDrizzle,
+        314,
+        "heavy shower rain and drizzle",
+    ),
+    (ShowerDrizzle, 321, "shower drizzle"),
+    (LightRain, 500, "light rain"),
+    (ModerateRain, 501, "moderate rain"),
+    (HeavyRain, 502, "heavy intensity rain"),
+    (VeryHeavyRain, 503, "very heavy rain"),
+    (ExtremeRain, 504, "extreme rain"),
+    (FreezingRain, 511, "freezing rain"),
+    (LightShowerRain, 520, "light intensity shower rain"),
+    (ShowerRain, 521, "shower rain"),
+    (HeavyShowerRain, 522, "heavy intensity shower rain"),
+    (RaggedShowerRain, 531, "ragged shower rain"),
+    (LightSnow, 600, "light snow"),
+    (Snow, 601, "snow"),
+    (HeavySnow, 602, "heavy snow"),
+    (Sleet, 611, "sleet"),
+    (LightShowerSleet, 612, "light shower sleet"),
+    (ShowerSleet, 613, "shower sleet"),
+    (LightRainAndSnow, 615, "light rain and snow"),
+    (RainAndSnow, 616, "rain and snow"),
+    (LightShowerSnow, 620, "light shower snow"),
+    (ShowerSnow, 621, "shower snow"),
+    (HeavyShowerSnow, 622, "heavy shower snow"),
+    (Mist, 701, "mist"),
+    (Smoke, 711, "smoke"),
+    (Haze, 721, "haze"),
+    (SandDustWhirls, 731, "sand/dust whirls"),
+    (Fog, 741, "fog"),
+    (Sand, 751, "sand"),
+    (Dust, 761, "dust"),
+    (VolcanicAsh, 762, "volcanic ash"),
+    (Squalls, 771, "squalls"),
+    (Tornado, 781, "tornado"),
+    (ClearSky, 800, "clear sky"),
+    (FewClouds, 801, "few clouds"),
+    (ScatteredClouds, 802, "scattered clouds"),
+    (BrokenClouds, 803, "broken clouds"),
+    (OvercastClouds, 804, "overcast clouds"),
+];
+
+impl Condition {
+    /// Condition of a weather condition id (e.g. `Weather::id`).
+    pub fn from_id(id: u64) -> Condition {
+        TABLE
+            .iter()
+            .find(|(_, i, _)| *i == id)
+            .map_or(Unknown(id), |(c, _, _)| *c)
+    }
+
+    /// Weather condition id.
+    pub fn id(&self) -> u64 {
+        match self {
+            Unknown(id) => *id,
+            c => TABLE.iter().find(|(t, _, _)| t == c).unwrap().1,
+        }
+    }
+
+    /// English description as delivered with `lang` `"en"`.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Unknown(_) => "unknown",
+            c => TABLE.iter().find(|(t, _, _)| t == c).unwrap().2,
+        }
+    }
+
+    /// Group of this condition.
+    pub fn group(&self) -> Group {
+        match self.id() {
+            200..=299 => Group::Thunderstorm,
+            300..=399 => Group::Drizzle,
+            500..=599 => Group::Rain,
+            600..=699 => Group::Snow,
+            700..=799 => Group::Atmosphere,
+            800 => Group::Clear,
+            801..=899 => Group::Clouds,
+            _ => Group::Unknown,
+        }
+    }
+
+    /// Name of the group as delivered in `Weather::main`
+    /// (atmospheric conditions use their own name like `"Mist"` or `"Fog"`).
+    pub fn main(&self) -> &'static str {
+        match self {
+            Mist => "Mist",
+            Smoke => "Smoke",
+            Haze => "Haze",
+            SandDustWhirls | Dust => "Dust",
+            Fog => "Fog",
+            Sand => "Sand",
+            VolcanicAsh => "Ash",
+            Squalls => "Squall",
+            Tornado => "Tornado",
+            c => match c.group() {
+                Group::Thunderstorm => "Thunderstorm",
+                Group::Drizzle => "Drizzle",
+                Group::Rain => "Rain",
+                Group::Snow => "Snow",
+                Group::Clear => "Clear",
+                Group::Clouds => "Clouds",
+                Group::Atmosphere | Group::Unknown => "",
+            },
+        }
+    }
+
+    /// Intensity of precipitation or thunderstorm (`Intensity::None` for everything else).
+    pub fn intensity(&self) -> Intensity {
+        match self {
+            ThunderstormWithLightRain
+            | LightThunderstorm
+            | RaggedThunderstorm
+            | ThunderstormWithLightDrizzle
+            | LightDrizzle
+            | LightDrizzleRain
+            | LightRain
+            | LightShowerRain
+            | RaggedShowerRain
+            | LightSnow
+            | LightShowerSleet
+            | LightRainAndSnow
+            | LightShowerSnow => Intensity::Light,
+            ThunderstormWithRain
+            | Thunderstorm
+            | ThunderstormWithDrizzle
+            | Drizzle
+            | DrizzleRain
+            | ShowerRainAndDrizzle
+            | ShowerDrizzle
+            | ModerateRain
+            | FreezingRain
+            | ShowerRain
+            | Snow
+            | Sleet
+            | ShowerSleet
+            | RainAndSnow
+            | ShowerSnow => Intensity::Moderate,
+            ThunderstormWithHeavyRain
+            | HeavyThunderstorm
+            | ThunderstormWithHeavyDrizzle
+            | HeavyDrizzle
+            | HeavyDrizzleRain
+            | HeavyShowerRainAndDrizzle
+            | HeavyRain
+            | HeavyShowerRain
+            | HeavySnow
+            | HeavyShowerSnow => Intensity::Heavy,
+            VeryHeavyRain | ExtremeRain => Intensity::Extreme,
+            _ => Intensity::None,
+        }
+    }
+
+    /// Rough severity from `0` (harmless) to `4` (dangerous).
+    /// Thunderstorms rank one level above their intensity, tornados, squalls and
+    /// volcanic ash always rank highest.
+    pub fn severity(&self) -> u8 {
+        match self {
+            Tornado | Squalls | VolcanicAsh => 4,
+            SandDustWhirls | Sand | Dust | Smoke => 2,
+            Fog | FreezingRain => 2,
+            Mist | Haze => 1,
+            c => {
+                let level = c.intensity() as u8;
+                match c.group() {
+                    Group::Thunderstorm => (level + 1).min(4),
+                    _ => level,
+                }
+            }
+        }
+    }
+
+    /// Whether any rain, drizzle or snow falls.
+    pub fn is_precipitation(&self) -> bool {
+        match self.group() {
+            Group::Drizzle | Group::Rain | Group::Snow => true,
+            Group::Thunderstorm => !matches!(
+                self,
+                LightThunderstorm | Thunderstorm | HeavyThunderstorm | RaggedThunderstorm
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether there is a thunderstorm.
+    pub fn is_thunderstorm(&self) -> bool {
+        self.group() == Group::Thunderstorm
+    }
+
+    /// Whether the precipitation is or contains snow or sleet.
+    pub fn is_snow(&self) -> bool {
+        self.group() == Group::Snow
+    }
+
+    /// Whether the precipitation may freeze on contact.
+    pub fn is_freezing(&self) -> bool {
+        matches!(self, FreezingRain | Sleet | LightShowerSleet | ShowerSleet)
+    }
+
+    /// Whether visibility is reduced by an atmospheric phenomenon like fog or dust.
+    pub fn is_atmosphere(&self) -> bool {
+        self.group() == Group::Atmosphere
+    }
+}
+
+impl Weather {
+    /// Typed weather condition of `id`.
+    pub fn condition(&self) -> Condition {
+        Condition::from_id(self.id)
+    }
+}
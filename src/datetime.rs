@@ -0,0 +1,203 @@
+use crate::CurrentWeather;
+use std::time::Duration;
+
+impl CurrentWeather {
+    /// Whether `dt` lies between sunrise and sunset.
+    /// `None` if the report has no sunrise or sunset (e.g. during polar day or polar night).
+    pub fn is_daytime(&self) -> Option<bool> {
+        Some(self.dt >= self.sys.sunrise? && self.dt < self.sys.sunset?)
+    }
+
+    /// Time between sunrise and sunset.
+    pub fn day_length(&self) -> Option<Duration> {
+        seconds(self.sys.sunset? - self.sys.sunrise?)
+    }
+
+    /// Time from `dt` until sunrise. `None` if sunrise has already passed.
+    pub fn time_until_sunrise(&self) -> Option<Duration> {
+        seconds(self.sys.sunrise? - self.dt)
+    }
+
+    /// Time from `dt` until sunset. `None` if sunset has already passed.
+    pub fn time_until_sunset(&self) -> Option<Duration> {
+        seconds(self.sys.sunset? - self.dt)
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar
+/// (see http://howardhinnant.github.io/date_algorithms.html#days_from_civil).
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Year, month and day of a number of days since 1970-01-01
+/// (see http://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Duration of a non-negative number of seconds.
+fn seconds(seconds: i64) -> Option<Duration> {
+    match seconds >= 0 {
+        true => Some(Duration::from_secs(seconds as u64)),
+        false => None,
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub use with_chrono::WithChrono;
+#[cfg(feature = "time")]
+pub use with_time::WithTime;
+
+/// Date and time as `chrono` types (requires feature `chrono`)
+#[cfg(feature = "chrono")]
+mod with_chrono {
+    use crate::CurrentWeather;
+    use chrono::{DateTime, FixedOffset, Utc};
+    use std::convert::TryFrom;
+
+    /// Times of a weather report as `chrono` types (requires feature `chrono`).
+    /// `WithTime` provides the same times for the `time` crate (prefixed `time_`).
+    pub trait WithChrono {
+        /// Local time offset of the location (UTC if the report has an invalid offset).
+        fn chrono_offset(&self) -> FixedOffset;
+        /// Time of data calculation in UTC. `None` if out of the range of `chrono`.
+        fn chrono_dt_utc(&self) -> Option<DateTime<Utc>>;
+        /// Time of data calculation in local time of the location.
+        fn chrono_dt_local(&self) -> Option<DateTime<FixedOffset>>;
+        /// Sunrise time in UTC.
+        fn chrono_sunrise_utc(&self) -> Option<DateTime<Utc>>;
+        /// Sunrise time in local time of the location.
+        fn chrono_sunrise_local(&self) -> Option<DateTime<FixedOffset>>;
+        /// Sunset time in UTC.
+        fn chrono_sunset_utc(&self) -> Option<DateTime<Utc>>;
+        /// Sunset time in local time of the location.
+        fn chrono_sunset_local(&self) -> Option<DateTime<FixedOffset>>;
+    }
+
+    fn utc(unix: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(unix, 0)
+    }
+
+    impl CurrentWeather {
+        fn chrono_local(&self, unix: i64) -> Option<DateTime<FixedOffset>> {
+            Some(utc(unix)?.with_timezone(&self.chrono_offset()))
+        }
+    }
+
+    impl WithChrono for CurrentWeather {
+        fn chrono_offset(&self) -> FixedOffset {
+            i32::try_from(self.timezone)
+                .ok()
+                .and_then(FixedOffset::east_opt)
+                .unwrap_or(FixedOffset::east_opt(0).unwrap())
+        }
+
+        fn chrono_dt_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.dt)
+        }
+
+        fn chrono_dt_local(&self) -> Option<DateTime<FixedOffset>> {
+            self.chrono_local(self.dt)
+        }
+
+        fn chrono_sunrise_utc(&self) -> Option<DateTime<Utc>> {
+            self.sys.sunrise.and_then(utc)
+        }
+
+        fn chrono_sunrise_local(&self) -> Option<DateTime<FixedOffset>> {
+            self.sys.sunrise.and_then(|t| self.chrono_local(t))
+        }
+
+        fn chrono_sunset_utc(&self) -> Option<DateTime<Utc>> {
+            self.sys.sunset.and_then(utc)
+        }
+
+        fn chrono_sunset_local(&self) -> Option<DateTime<FixedOffset>> {
+            self.sys.sunset.and_then(|t| self.chrono_local(t))
+        }
+    }
+}
+
+/// Date and time as `time` types (requires feature `time`)
+#[cfg(feature = "time")]
+mod with_time {
+    use crate::CurrentWeather;
+    use std::convert::TryFrom;
+    use time::{OffsetDateTime, UtcOffset};
+
+    /// Times of a weather report as `time` types (requires feature `time`).
+    /// `WithChrono` provides the same times for the `chrono` crate (prefixed `chrono_`).
+    pub trait WithTime {
+        /// Local time offset of the location (UTC if the report has an invalid offset).
+        fn time_offset(&self) -> UtcOffset;
+        /// Time of data calculation in UTC. `None` if out of the range of `time`.
+        fn time_dt_utc(&self) -> Option<OffsetDateTime>;
+        /// Time of data calculation in local time of the location.
+        fn time_dt_local(&self) -> Option<OffsetDateTime>;
+        /// Sunrise time in UTC.
+        fn time_sunrise_utc(&self) -> Option<OffsetDateTime>;
+        /// Sunrise time in local time of the location.
+        fn time_sunrise_local(&self) -> Option<OffsetDateTime>;
+        /// Sunset time in UTC.
+        fn time_sunset_utc(&self) -> Option<OffsetDateTime>;
+        /// Sunset time in local time of the location.
+        fn time_sunset_local(&self) -> Option<OffsetDateTime>;
+    }
+
+    fn utc(unix: i64) -> Option<OffsetDateTime> {
+        OffsetDateTime::from_unix_timestamp(unix).ok()
+    }
+
+    impl CurrentWeather {
+        fn time_local(&self, unix: i64) -> Option<OffsetDateTime> {
+            utc(unix)?.checked_to_offset(self.time_offset())
+        }
+    }
+
+    impl WithTime for CurrentWeather {
+        fn time_offset(&self) -> UtcOffset {
+            i32::try_from(self.timezone)
+                .ok()
+                .and_then(|seconds| UtcOffset::from_whole_seconds(seconds).ok())
+                .unwrap_or(UtcOffset::UTC)
+        }
+
+        fn time_dt_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.dt)
+        }
+
+        fn time_dt_local(&self) -> Option<OffsetDateTime> {
+            self.time_local(self.dt)
+        }
+
+        fn time_sunrise_utc(&self) -> Option<OffsetDateTime> {
+            self.sys.sunrise.and_then(utc)
+        }
+
+        fn time_sunrise_local(&self) -> Option<OffsetDateTime> {
+            self.sys.sunrise.and_then(|t| self.time_local(t))
+        }
+
+        fn time_sunset_utc(&self) -> Option<OffsetDateTime> {
+            self.sys.sunset.and_then(utc)
+        }
+
+        fn time_sunset_local(&self) -> Option<OffsetDateTime> {
+            self.sys.sunset.and_then(|t| self.time_local(t))
+        }
+    }
+}
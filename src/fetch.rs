@@ -0,0 +1,275 @@
+use crate::cache::{self, Cached, Failure};
+use crate::keys::{self, KeyPool};
+use crate::ratelimit::{self, RateLimitError};
+use crate::usage::{self, Usage};
+use futures::channel::oneshot;
+use http::header::CONTENT_TYPE;
+use reqwest::blocking::Response;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+/// Base URL of all *OpenWeatherMap* APIs.
+pub(crate) const API: &str = "http://api.openweathermap.org";
+
+/// Reason why `send()` did not send a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NotSent {
+    /// refused by the enabled rate limiter
+    RateLimited(RateLimitError),
+    /// no key of the enabled pool is usable (see `KeyPool::usable_in()`)
+    NoUsableKey(Option<Duration>),
+    /// any other reason
+    Other(String),
+}
+
+impl fmt::Display for NotSent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotSent::RateLimited(e) => e.fmt(f),
+            NotSent::NoUsableKey(_) => f.write_str("no usable API key in pool"),
+            NotSent::Other(e) => f.write_str(e),
+        }
+    }
+}
+
+impl From<String> for NotSent {
+    fn from(e: String) -> NotSent {
+        NotSent::Other(e)
+    }
+}
+
+impl From<NotSent> for String {
+    fn from(e: NotSent) -> String {
+        e.to_string()
+    }
+}
+
+/// Fetches `url` once and parses the JSON response into `T`.
+/// #### Return value
+/// - ⇒ `Ok(T)`: parsed response
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+pub(crate) fn get<T: DeserializeOwned>(url: &str) -> Result<T, String> {
+    serde_json::from_str(&get_text(url)?).map_err(|e| e.to_string())
+}
+
+/// Fetches `url` once (or takes it from the enabled caches) and returns the response body as text.
+pub(crate) fn get_text(url: &str) -> Result<String, String> {
+    Ok(get_cached(url, false)?.value)
+}
+
+/// Like `get_text()` but tells where the response came from.
+/// If `serve_stale` is set, outdated responses of the disk cache are served while
+/// *OpenWeatherMap* is unavailable.
+pub(crate) fn get_cached(url: &str, serve_stale: bool) -> Result<Cached<String>, String> {
+    let mut api_key = None;
+    let mut cached = cached(url, serve_stale, || {
+        let (response, key) =
+            send(url, |url| reqwest::blocking::get(url)).map_err(|e| Failure::Refused(e.into()))?;
+        api_key = key;
+        let response = match response {
+            Ok(response) if response.status().is_server_error() => {
+                return Err(Failure::Unavailable(response.status().to_string()))
+            }
+            Ok(response) => response,
+            Err(e) => return Err(Failure::Unavailable(e.to_string())),
+        };
+        receive(Ok(response))
+            .map_err(Failure::Refused)?
+            .text()
+            .map_err(|e| Failure::Unavailable(e.to_string()))
+    })?;
+    if !cached.stale {
+        cached.api_key = api_key;
+    }
+    Ok(cached)
+}
+
+/// Answers a request for `url` from the enabled caches or by calling `fetch`
+/// (see `cache::fetch()`).
+pub(crate) fn cached<E, F>(url: &str, serve_stale: bool, fetch: F) -> Result<Cached<String>, E>
+where
+    F: FnOnce() -> Result<String, Failure<E>>,
+{
+    cache::fetch(
+        cache::current().as_ref(),
+        cache::current_disk().as_ref(),
+        url,
+        serve_stale,
+        fetch,
+    )
+}
+
+/// Fetches `url` once and returns the raw response body.
+pub(crate) fn get_bytes(url: &str) -> Result<Vec<u8>, String> {
+    match receive(send(url, |url| reqwest::blocking::get(url))?.0)?.bytes() {
+        Ok(bytes) => Ok(bytes.to_vec()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Posts `body` as JSON to `url` once and parses the JSON response into `T`.
+pub(crate) fn post<B: Serialize, T: DeserializeOwned>(url: &str, body: &B) -> Result<T, String> {
+    parse(request_with(
+        keys::current().as_ref(),
+        Method::POST,
+        url,
+        Some(body),
+    )?)
+}
+
+/// Sends a request with an optional JSON `body` to `url` once and returns the successful response.
+/// The API key of `url` is never replaced by an enabled key pool (see module `keys`), because
+/// Agro Monitoring has keys of its own and stations belong to the key which registered them.
+pub(crate) fn request<B: Serialize>(
+    method: Method,
+    url: &str,
+    body: Option<&B>,
+) -> Result<Response, String> {
+    request_with(None, method, url, body)
+}
+
+/// Like `request()` but with the key pool given by `pool`.
+fn request_with<B: Serialize>(
+    pool: Option<&KeyPool>,
+    method: Method,
+    url: &str,
+    body: Option<&B>,
+) -> Result<Response, String> {
+    let body = match body {
+        Some(body) => Some(serde_json::to_string(body).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let client = reqwest::blocking::Client::new();
+    let (response, _) = send_with(pool, usage::current().as_ref(), url, |url| {
+        let mut request = client.request(method.clone(), url);
+        if let Some(body) = &body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone());
+        }
+        request.send()
+    })?;
+    receive(response)
+}
+
+/// Sends the request `send` builds for `url` once it is `admit()`ted and counts it in the
+/// enabled usage counters (see module `usage`) if a response arrived. If a key pool is
+/// enabled (see module `keys`), the API key of `url` is replaced by the keys of the pool one
+/// after another until the response is neither `401 Unauthorized` nor `429 Too Many Requests`.
+/// #### Return value
+/// - ⇒ `Ok((reqwest::Result<Response>, Option<String>))`: the last response and the key of the
+///   pool it was requested with
+/// - ⇒ `Err(NotSent)`: the request was not admitted or no key of the pool is usable
+pub(crate) fn send<F>(
+    url: &str,
+    send: F,
+) -> Result<(reqwest::Result<Response>, Option<String>), NotSent>
+where
+    F: Fn(&str) -> reqwest::Result<Response>,
+{
+    send_with(
+        keys::current().as_ref(),
+        usage::current().as_ref(),
+        url,
+        send,
+    )
+}
+
+/// Like `send()` but with the key pool given by `pool` and the usage counters given by `usage`.
+pub(crate) fn send_with<F>(
+    pool: Option<&KeyPool>,
+    usage: Option<&Usage>,
+    url: &str,
+    send: F,
+) -> Result<(reqwest::Result<Response>, Option<String>), NotSent>
+where
+    F: Fn(&str) -> reqwest::Result<Response>,
+{
+    let send = |url: &str| {
+        admit()?;
+        let response = send(url);
+        if let (Some(usage), Ok(_)) = (usage, &response) {
+            // failing to save the counters must not break fetching weather
+            usage.record(url).unwrap_or(());
+        }
+        Ok::<_, NotSent>(response)
+    };
+    let pool = match pool {
+        Some(pool) => pool,
+        None => return Ok((send(url)?, None)),
+    };
+    let mut rejected = None;
+    for key in pool.candidates() {
+        let url = keys::with_key(url, &key)?;
+        let response = send(&url)?;
+        let status = response.as_ref().ok().map(|r| r.status().as_u16());
+        pool.record(&key, &url, status);
+        match status {
+            Some(401) | Some(429) => rejected = Some((response, Some(key))),
+            _ => return Ok((response, Some(key))),
+        }
+    }
+    rejected.ok_or_else(|| NotSent::NoUsableKey(pool.usable_in()))
+}
+
+/// Takes a token from the enabled rate limiter (see module `ratelimit`) before a request is sent.
+fn admit() -> Result<(), NotSent> {
+    if let Some(limiter) = ratelimit::current() {
+        limiter.acquire().map_err(NotSent::RateLimited)?;
+    }
+    Ok(())
+}
+
+/// Turns any http failure or unsuccessful status into an error message.
+fn receive(response: reqwest::Result<Response>) -> Result<Response, String> {
+    match response {
+        Ok(response) => match response.status().is_success() {
+            true => Ok(response),
+            false => Err(response.status().to_string()),
+        },
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parses the JSON body of a successful response.
+pub(crate) fn parse<T: DeserializeOwned>(response: Response) -> Result<T, String> {
+    match response.text() {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Runs a request within a separate thread like `init()` does, so that the blocking
+/// http client never runs inside the caller's executor. The result is passed back through
+/// a channel which wakes the awaiting task instead of blocking it.
+pub(crate) async fn spawn<T, F>(request: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    thread::spawn(move || tx.send(request()).unwrap_or(()));
+    rx.await
+        .unwrap_or_else(|_| Err("request thread panicked".to_string()))
+}
+
+/// Runs `get()` within a separate thread (see `spawn()`).
+pub(crate) async fn get_async<T>(url: String) -> Result<T, String>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    spawn(move || get(&url)).await
+}
+
+/// Runs `post()` within a separate thread (see `spawn()`).
+pub(crate) async fn post_async<B, T>(url: String, body: B) -> Result<T, String>
+where
+    B: Serialize + Send + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    spawn(move || post(&url, &body)).await
+}
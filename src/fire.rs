@@ -0,0 +1,79 @@
+use crate::coordinates;
+use crate::fetch::{get_async, API};
+use crate::Coord;
+use serde::{Deserialize, Serialize};
+
+/// Fire Weather Index value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FireIndex {
+    /// Fire Weather Index
+    pub fwi: f64,
+}
+
+/// Fire danger rating derived from the Fire Weather Index
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DangerRating {
+    /// Rating from `0` (very low) to `5` (extreme)
+    pub value: u8,
+    /// Rating name (Very low, Low, Moderate, High, Very high, Extreme)
+    pub description: String,
+}
+
+/// Fire Weather Index for a single point in time
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FireWeatherItem {
+    /// Fire Weather Index value
+    pub main: FireIndex,
+    /// Danger rating
+    pub danger_rating: DangerRating,
+    /// Time of data calculation, unix, UTC
+    pub dt: i64,
+}
+
+/// Fire Weather Index report
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FireWeather {
+    /// report origin coordinates
+    pub coord: Coord,
+    /// vector with one item for the current value or one item per day for forecasts
+    pub list: Vec<FireWeatherItem>,
+}
+
+/// Fetch current Fire Weather Index once.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+/// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+/// #### Return value
+/// - ⇒ `Ok(FireWeather)`: Fire Weather Index in a nested struct called `FireWeather`
+///   (see also [*OpenWeatherMap* documentation](https://openweathermap.org/api/fire-weather-index) for details)
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+///     - a message if `location` is not a geographical coordinate
+pub async fn fire_weather(location: &str, api_key: &str) -> Result<FireWeather, String> {
+    let (lat, lon) = coordinates(location)?;
+    get_async(format!(
+        "{}/data/2.5/fwi?lat={}&lon={}&appid={}",
+        API, lat, lon, api_key
+    ))
+    .await
+}
+
+/// Fetch daily Fire Weather Index forecast once.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+/// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+/// #### Return value
+/// - ⇒ `Ok(FireWeather)`: Fire Weather Index forecast in a nested struct called `FireWeather`
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+///     - a message if `location` is not a geographical coordinate
+pub async fn fire_weather_forecast(location: &str, api_key: &str) -> Result<FireWeather, String> {
+    let (lat, lon) = coordinates(location)?;
+    get_async(format!(
+        "{}/data/2.5/fwi/forecast?lat={}&lon={}&appid={}",
+        API, lat, lon, api_key
+    ))
+    .await
+}
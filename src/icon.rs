@@ -0,0 +1,119 @@
+use crate::{Condition, Group, Weather};
+
+/// Resolution of an official weather icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSize {
+    /// 50x50 pixels
+    X1,
+    /// 100x100 pixels
+    X2,
+    /// 200x200 pixels
+    X4,
+}
+
+/// URL of the official PNG image of a weather icon id like `"10d"`.
+pub fn icon_url(icon: &str, size: IconSize) -> String {
+    let suffix = match size {
+        IconSize::X1 => "",
+        IconSize::X2 => "@2x",
+        IconSize::X4 => "@4x",
+    };
+    format!("http://openweathermap.org/img/wn/{}{}.png", icon, suffix)
+}
+
+impl Weather {
+    /// URL of the official PNG image of `icon`.
+    pub fn icon_url(&self, size: IconSize) -> String {
+        icon_url(&self.icon, size)
+    }
+
+    /// Whether `icon` denotes day time (icon ids ending with `d`).
+    pub fn is_day(&self) -> bool {
+        self.icon.ends_with('d')
+    }
+
+    /// Whether `icon` denotes night time (icon ids ending with `n`).
+    pub fn is_night(&self) -> bool {
+        self.icon.ends_with('n')
+    }
+
+    /// Unicode emoji of the condition at the time of day `icon` denotes.
+    pub fn emoji(&self) -> &'static str {
+        self.condition().emoji(!self.is_night())
+    }
+
+    /// [Nerd Font](https://www.nerdfonts.com/cheat-sheet) glyph of the condition at the time
+    /// of day `icon` denotes.
+    pub fn nerd_font(&self) -> char {
+        self.condition().nerd_font(!self.is_night())
+    }
+
+    /// [Weather Icons](https://erikflowers.github.io/weather-icons/) CSS class of the condition
+    /// at the time of day `icon` denotes.
+    pub fn weather_icons(&self) -> String {
+        self.condition().weather_icons(!self.is_night())
+    }
+}
+
+impl Condition {
+    /// Unicode emoji of this condition by day (`day` is `true`) or by night.
+    pub fn emoji(&self, day: bool) -> &'static str {
+        use Condition::*;
+        match self {
+            LightThunderstorm | Thunderstorm | HeavyThunderstorm | RaggedThunderstorm => "🌩️",
+            Sleet | LightShowerSleet | ShowerSleet | FreezingRain => "🌨️",
+            Mist | Haze | Fog => "🌫️",
+            Smoke | SandDustWhirls | Sand | Dust | Squalls => "💨",
+            VolcanicAsh => "🌋",
+            Tornado => "🌪️",
+            ClearSky if day => "☀️",
+            ClearSky => "🌙",
+            FewClouds if day => "🌤️",
+            ScatteredClouds if day => "⛅",
+            c => match c.group() {
+                Group::Thunderstorm => "⛈️",
+                Group::Drizzle | Group::Rain if day && c.id() >= 520 => "🌦️",
+                Group::Drizzle | Group::Rain => "🌧️",
+                Group::Snow => "❄️",
+                Group::Clouds => "☁️",
+                _ => "",
+            },
+        }
+    }
+
+    /// [Nerd Font](https://www.nerdfonts.com/cheat-sheet) glyph (`nf-weather-*`) of this
+    /// condition by day (`day` is `true`) or by night.
+    pub fn nerd_font(&self, day: bool) -> char {
+        use Condition::*;
+        match self {
+            Sleet | LightShowerSleet | ShowerSleet | FreezingRain => '\u{e3ad}',
+            Mist | Haze | Fog => '\u{e313}',
+            Smoke => '\u{e35c}',
+            SandDustWhirls | Dust => '\u{e35d}',
+            Sand => '\u{e37a}',
+            VolcanicAsh => '\u{e3c0}',
+            Squalls => '\u{e34b}',
+            Tornado => '\u{e351}',
+            ClearSky if day => '\u{e30d}',
+            ClearSky => '\u{e32b}',
+            FewClouds | ScatteredClouds if day => '\u{e302}',
+            FewClouds | ScatteredClouds => '\u{e37e}',
+            c => match c.group() {
+                Group::Thunderstorm => '\u{e31d}',
+                Group::Drizzle => '\u{e31b}',
+                Group::Rain if c.id() >= 520 => '\u{e319}',
+                Group::Rain if day => '\u{e308}',
+                Group::Rain => '\u{e325}',
+                Group::Snow => '\u{e31a}',
+                Group::Clouds => '\u{e312}',
+                _ => '\u{e374}',
+            },
+        }
+    }
+
+    /// [Weather Icons](https://erikflowers.github.io/weather-icons/) CSS class of this
+    /// condition by day (`day` is `true`) or by night (e.g. `"wi-owm-day-500"`).
+    pub fn weather_icons(&self, day: bool) -> String {
+        format!("wi-owm-{}-{}", if day { "day" } else { "night" }, self.id())
+    }
+}
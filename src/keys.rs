@@ -0,0 +1,254 @@
+//! Pool of several API keys which requests rotate among and fail over between.
+//!
+//! Once a pool is enabled, the `api_key` parameters of the weather functions of this crate
+//! (including `init()`) are replaced by keys of the pool. If *OpenWeatherMap* answers
+//! `401 Unauthorized` or `429 Too Many Requests`, the request is repeated with the next key.
+//! `Agro` and `Stations` always use the key they were created with, because Agro Monitoring
+//! has keys of its own and stations belong to the key which registered them.
+//!
+//! `KeyPool::on_served()` tells which key served each response of any function
+//! (`weather_cached()` also returns it along with the response).
+//! If no key is usable, the polling thread of `init()` waits until a key has rested
+//! (or the next poll if none will recover) before it tries again.
+//!
+//! ```no_run
+//! use openweathermap::keys::{self, KeyPool, Rotation};
+//!
+//! let pool = KeyPool::new(&["<APIKEY1>", "<APIKEY2>"], Rotation::RoundRobin)
+//!     .on_served(|served| println!("{} served by {}", served.url, served.api_key));
+//! keys::enable(pool);
+//! let current = openweathermap::blocking::weather_cached("Berlin,DE", "metric", "en", "").unwrap();
+//! println!("served by {:?}", current.api_key);
+//! ```
+
+use reqwest::Url;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Order in which the keys of a pool are used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    /// One key after another
+    RoundRobin,
+    /// Key with the most calls left of its quota first (keys without quota count as unlimited)
+    RemainingQuota,
+}
+
+/// State of a key within a pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStatus {
+    /// The API key
+    pub key: String,
+    /// Calls sent with this key
+    pub calls: u64,
+    /// Calls left of the quota (`None` if unlimited)
+    pub remaining: Option<u64>,
+    /// Whether the key was rejected with `401 Unauthorized` and is not used anymore
+    pub invalid: bool,
+    /// Whether the key was rejected with `429 Too Many Requests` and is resting
+    pub resting: bool,
+}
+
+/// Response received for a request sent with a key of a pool (see `KeyPool::on_served()`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Served {
+    /// URL the request was sent to (including the key)
+    pub url: String,
+    /// Key the request was sent with
+    pub api_key: String,
+    /// HTTP status of the response
+    pub status: u16,
+}
+
+/// Callback of `KeyPool::on_served()`
+type OnServed = Arc<dyn Fn(&Served) + Send + Sync>;
+
+struct Entry {
+    key: String,
+    quota: Option<u64>,
+    calls: u64,
+    invalid: bool,
+    resting_until: Option<Instant>,
+}
+
+impl Entry {
+    fn remaining(&self) -> Option<u64> {
+        self.quota.map(|quota| quota.saturating_sub(self.calls))
+    }
+
+    fn usable(&self) -> bool {
+        !self.invalid
+            && self.remaining() != Some(0)
+            && self.resting_until.is_none_or(|t| t <= Instant::now())
+    }
+}
+
+struct State {
+    entries: Vec<Entry>,
+    next: usize,
+}
+
+/// Pool of API keys which can be shared between threads (clones share the same state)
+#[derive(Clone)]
+pub struct KeyPool {
+    rotation: Rotation,
+    rest: Duration,
+    state: Arc<Mutex<State>>,
+    served: Option<OnServed>,
+}
+
+impl KeyPool {
+    /// Creates a pool of `keys` without quotas. Keys answered with `429 Too Many Requests`
+    /// rest for one minute.
+    pub fn new(keys: &[&str], rotation: Rotation) -> KeyPool {
+        KeyPool {
+            rotation,
+            rest: Duration::from_secs(60),
+            state: Arc::new(Mutex::new(State {
+                entries: keys
+                    .iter()
+                    .map(|key| Entry {
+                        key: key.to_string(),
+                        quota: None,
+                        calls: 0,
+                        invalid: false,
+                        resting_until: None,
+                    })
+                    .collect(),
+                next: 0,
+            })),
+            served: None,
+        }
+    }
+
+    /// Limits the calls of `key` to `quota` (e.g. the calls left this month).
+    pub fn with_quota(self, key: &str, quota: u64) -> KeyPool {
+        for entry in self.state().entries.iter_mut() {
+            if entry.key == key {
+                entry.quota = Some(quota);
+            }
+        }
+        self
+    }
+
+    /// Sets the time a key answered with `429 Too Many Requests` is not used.
+    pub fn with_rest(mut self, rest: Duration) -> KeyPool {
+        self.rest = rest;
+        self
+    }
+
+    /// Calls `served` for every response received with a key of the pool (including those
+    /// rejected with `401 Unauthorized` or `429 Too Many Requests`).
+    pub fn on_served<F>(mut self, served: F) -> KeyPool
+    where
+        F: Fn(&Served) + Send + Sync + 'static,
+    {
+        self.served = Some(Arc::new(served));
+        self
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Usable keys in the order they should be tried for the next request.
+    pub(crate) fn candidates(&self) -> Vec<String> {
+        let mut state = self.state();
+        let count = state.entries.len();
+        let mut order: Vec<usize> = (0..count).map(|i| (state.next + i) % count).collect();
+        match self.rotation {
+            Rotation::RoundRobin => state.next = (state.next + 1) % count.max(1),
+            // stable sort keeps the given order among keys with equal quota
+            Rotation::RemainingQuota => order.sort_by_key(|&i| {
+                std::cmp::Reverse(state.entries[i].remaining().unwrap_or(u64::MAX))
+            }),
+        }
+        order
+            .into_iter()
+            .filter(|&i| state.entries[i].usable())
+            .map(|i| state.entries[i].key.clone())
+            .collect()
+    }
+
+    /// Time until the next key is usable again after resting.
+    /// `None` if no key is resting (so that no key will become usable again by itself).
+    pub(crate) fn usable_in(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.state()
+            .entries
+            .iter()
+            .filter(|e| !e.invalid && e.remaining() != Some(0))
+            .filter_map(|e| e.resting_until)
+            .map(|t| t.saturating_duration_since(now))
+            .min()
+    }
+
+    /// Counts a call of `url` sent with `key` and takes the key out of rotation if it was
+    /// rejected with `status`.
+    pub(crate) fn record(&self, key: &str, url: &str, status: Option<u16>) {
+        let rest = self.rest;
+        if let Some(entry) = self.state().entries.iter_mut().find(|e| e.key == key) {
+            entry.calls += 1;
+            match status {
+                Some(401) => entry.invalid = true,
+                Some(429) => entry.resting_until = Some(Instant::now() + rest),
+                _ => (),
+            }
+        }
+        if let (Some(served), Some(status)) = (&self.served, status) {
+            served(&Served {
+                url: url.to_string(),
+                api_key: key.to_string(),
+                status,
+            });
+        }
+    }
+
+    /// State of all keys.
+    pub fn status(&self) -> Vec<KeyStatus> {
+        let now = Instant::now();
+        self.state()
+            .entries
+            .iter()
+            .map(|entry| KeyStatus {
+                key: entry.key.clone(),
+                calls: entry.calls,
+                remaining: entry.remaining(),
+                invalid: entry.invalid,
+                resting: entry.resting_until.is_some_and(|t| t > now),
+            })
+            .collect()
+    }
+}
+
+/// Replaces the `appid` parameter of `url` by `key`.
+pub(crate) fn with_key(url: &str, key: &str) -> Result<String, String> {
+    let mut url = Url::parse(url).map_err(|e| e.to_string())?;
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| name != "appid")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("appid", key);
+    Ok(url.to_string())
+}
+
+static POOL: Mutex<Option<KeyPool>> = Mutex::new(None);
+
+/// Sends all following requests of this crate with keys of `pool`.
+pub fn enable(pool: KeyPool) {
+    *POOL.lock().unwrap_or_else(|e| e.into_inner()) = Some(pool);
+}
+
+/// Sends all following requests with the API key given to the functions again.
+pub fn disable() {
+    *POOL.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Pool currently enabled.
+pub fn current() -> Option<KeyPool> {
+    POOL.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
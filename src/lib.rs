@@ -8,8 +8,45 @@ use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
+pub mod agro;
 mod api;
+pub mod astro;
+pub mod bulk;
+pub mod cache;
+#[cfg(feature = "city-index")]
+pub mod cities;
+mod condition;
+mod datetime;
+mod fetch;
+mod fire;
+mod icon;
+pub mod keys;
+mod meteo;
+mod pressure;
+mod quantity;
+pub mod ratelimit;
+mod roadrisk;
+mod solar;
+pub mod stations;
+pub mod tiles;
+mod units;
+pub mod usage;
+mod wind;
+pub mod xml;
 pub use api::*;
+pub use condition::*;
+#[cfg(feature = "chrono")]
+pub use datetime::WithChrono;
+#[cfg(feature = "time")]
+pub use datetime::WithTime;
+pub use fire::*;
+pub use icon::*;
+pub use pressure::*;
+pub use quantity::*;
+pub use roadrisk::*;
+pub use solar::*;
+pub use units::*;
+pub use wind::*;
 
 #[cfg(test)]
 mod tests;
@@ -19,6 +56,28 @@ pub type Receiver = mpsc::Receiver<Result<CurrentWeather, String>>;
 /// Loading error messaage you get at the first call of `update()`.
 pub const LOADING: &str = "loading...";
 
+/// Format of a weather document requested by `weather_document()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// JSON document (default of the API)
+    Json,
+    /// XML document (can be parsed with `xml::parse()`)
+    Xml,
+    /// HTML snippet for embedding into a web page
+    Html,
+}
+
+impl Mode {
+    /// Mode name as used within the request URL.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Json => "json",
+            Mode::Xml => "xml",
+            Mode::Html => "html",
+        }
+    }
+}
+
 /// Spawns a thread which fetches the current weather from
 /// [openweathermap.org](https://openweathermap.org) periodically.
 /// #### Parameters
@@ -47,45 +106,60 @@ pub const LOADING: &str = "loading...";
 ///     pub type Receiver = std::sync::mpsc::Receiver<Result<openweathermap::CurrentWeather, String>>;
 ///    ```
 
+#[allow(clippy::empty_line_after_doc_comments, clippy::needless_return)]
 pub fn init(location: &str, units: &str, lang: &str, api_key: &str, poll_mins: u64) -> Receiver {
-    // generate correct request URL depending on city is id or name
-    let url = match location.parse::<u64>().is_ok() {
-        true => format!(
-            "http://api.openweathermap.org/data/2.5/weather?id={}&units={}&lang={}&appid={}",
-            location, units, lang, api_key
-        ),
-        false => {
-            let re = Regex::new(r"(-?\d+\.\d+)\s*,\s*(-?\d+\.\d+)").unwrap();
-            match re.captures(&location) {
-                Some(caps) => format!("http://api.openweathermap.org/data/2.5/weather?lat={}&lon={}&units={}&lang={}&appid={}",
-                            caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str(), units, lang, api_key ),
-                None => format!(
-                            "http://api.openweathermap.org/data/2.5/weather?q={}&units={}&lang={}&appid={}",
-                            location, units, lang, api_key ),
-            }
-        }
-    };
+    let url = weather_url(location, units, lang, api_key);
+    let units = units.parse().unwrap_or_default();
     // fork thread that continuously fetches weather updates every <poll_mins> minutes
     let period = Duration::from_secs(60 * poll_mins);
     let (tx, rx) = mpsc::channel();
     thread::spawn(move || {
         tx.send(Err(LOADING.to_string())).unwrap_or(());
         loop {
-            match reqwest::blocking::get(&url) {
-                Ok(response) => match response.status() {
-                    StatusCode::OK => match serde_json::from_str(&response.text().unwrap()) {
-                        Ok(w) => {
-                            tx.send(Ok(w)).unwrap_or(());
-                            if period == Duration::new(0, 0) {
-                                break;
-                            }
-                            thread::sleep(period);
-                        }
-                        Err(e) => tx.send(Err(e.to_string())).unwrap_or(()),
+            // network errors are not reported but retried
+            let response = fetch::cached(&url, false, || {
+                match fetch::send(&url, |url| reqwest::blocking::get(url))
+                    .map_err(|e| cache::Failure::Refused(Some(e)))?
+                    .0
+                {
+                    Ok(response) => match response.status() {
+                        StatusCode::OK => Ok(response.text().unwrap()),
+                        _ => Err(cache::Failure::Refused(Some(
+                            response.status().to_string().into(),
+                        ))),
                     },
-                    _ => tx.send(Err(response.status().to_string())).unwrap_or(()),
+                    Err(_e) => Err(cache::Failure::Unavailable(None)),
+                }
+            });
+            match response {
+                Ok(cached) => match serde_json::from_str::<CurrentWeather>(&cached.value) {
+                    Ok(mut w) => {
+                        w.units = units;
+                        tx.send(Ok(w)).unwrap_or(());
+                        if period == Duration::new(0, 0) {
+                            break;
+                        }
+                        thread::sleep(period);
+                    }
+                    Err(e) => tx.send(Err(e.to_string())).unwrap_or(()),
                 },
-                Err(_e) => (),
+                Err(Some(e)) => {
+                    tx.send(Err(e.to_string())).unwrap_or(());
+                    // do not retry before the rate limiter or the key pool allows it
+                    let wait = match e {
+                        fetch::NotSent::RateLimited(limited) => Some(limited.retry_after),
+                        fetch::NotSent::NoUsableKey(usable_in) => Some(usable_in.unwrap_or(period)),
+                        fetch::NotSent::Other(_) => None,
+                    };
+                    if let Some(wait) = wait {
+                        if period == Duration::new(0, 0) || wait == ratelimit::RateLimitError::NEVER
+                        {
+                            break;
+                        }
+                        thread::sleep(wait);
+                    }
+                }
+                Err(None) => (),
             }
         }
     });
@@ -93,6 +167,45 @@ pub fn init(location: &str, units: &str, lang: &str, api_key: &str, poll_mins: u
     return rx;
 }
 
+/// Generates the request URL of the current weather depending on whether `location`
+/// is a city ID, a coordinate or a city name.
+fn weather_url(location: &str, units: &str, lang: &str, api_key: &str) -> String {
+    match location.parse::<u64>().is_ok() {
+        true => format!(
+            "http://api.openweathermap.org/data/2.5/weather?id={}&units={}&lang={}&appid={}",
+            location, units, lang, api_key
+        ),
+        false => match coordinates(location) {
+            Ok((lat, lon)) => format!("http://api.openweathermap.org/data/2.5/weather?lat={}&lon={}&units={}&lang={}&appid={}",
+                        lat, lon, units, lang, api_key ),
+            Err(_) => format!(
+                        "http://api.openweathermap.org/data/2.5/weather?q={}&units={}&lang={}&appid={}",
+                        location, units, lang, api_key ),
+        },
+    }
+}
+
+/// Splits a location given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+/// into its latitude and longitude parts.
+pub(crate) fn coordinates(location: &str) -> Result<(&str, &str), String> {
+    let re = Regex::new(r"(-?\d+\.\d+)\s*,\s*(-?\d+\.\d+)").unwrap();
+    match re.captures(location) {
+        Some(caps) => Ok((caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str())),
+        None => Err(format!(
+            "location '{}' is not given by latitude and longitude",
+            location
+        )),
+    }
+}
+
+/// Report of the calls counted since `usage::enable()` (see module `usage`).
+/// #### Return value
+/// - ⇒ `None`: no call accounting enabled
+/// - ⇒ `Some(UsageReport)`: calls per day, endpoint and API key
+pub fn usage() -> Option<usage::UsageReport> {
+    usage::current().map(|usage| usage.report())
+}
+
 /// Get current weather update that the spawned thread could fetched.
 /// #### Parameters
 /// - `receiver`: the *channel receiver* from preceded call to `openweathermap::init()`
@@ -104,6 +217,7 @@ pub fn init(location: &str, units: &str, lang: &str, api_key: &str, poll_mins: u
 ///     - ⇒ `Err(String)`: Error message about any occured http or json issue
 ///         - e.g. `401 Unauthorized`: if your API key is invalid
 ///         - some json parser error message if response from OpenWeatherMap could not be parsed
+#[allow(clippy::doc_overindented_list_items, clippy::manual_ok_err)]
 pub fn update(receiver: &Receiver) -> Option<Result<CurrentWeather, String>> {
     match receiver.try_recv() {
         Ok(response) => Some(response),
@@ -134,6 +248,7 @@ pub fn update(receiver: &Receiver) -> Option<Result<CurrentWeather, String>> {
 /// - ⇒ `Err(String)`: Error message about any occured http or json issue
 ///         - e.g. `401 Unauthorized` if your API key is invalid
 ///         - some json parser error message if response from OpenWeatherMap could not be parsed
+#[allow(clippy::doc_overindented_list_items, clippy::single_match)]
 pub async fn weather(
     location: &str,
     units: &str,
@@ -156,6 +271,97 @@ pub async fn weather(
     }
 }
 
+/// Fetch current weather once as unparsed document in the given format.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`, `units`, `lang`, `api_key`: see `weather()`
+/// - `mode`: format of the document (JSON, XML or HTML)
+/// #### Return value
+/// - ⇒ `Ok(String)`: the document as sent by *OpenWeatherMap*
+/// - ⇒ `Err(String)`: Error message about any occured http issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+pub async fn weather_document(
+    location: &str,
+    units: &str,
+    lang: &str,
+    mode: Mode,
+    api_key: &str,
+) -> Result<String, String> {
+    let url = format!(
+        "{}&mode={}",
+        weather_url(location, units, lang, api_key),
+        mode.name()
+    );
+    fetch::spawn(move || fetch::get_text(&url)).await
+}
+
+/// Fetch current weather once and return it parsed together with the unparsed JSON response
+/// (e.g. to log exact payloads or to access fields this crate does not know yet).
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`, `units`, `lang`, `api_key`: see `weather()`
+/// #### Return value
+/// - ⇒ `Ok((CurrentWeather, String))`: parsed weather information and the JSON response it was parsed from
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+pub async fn weather_raw(
+    location: &str,
+    units: &str,
+    lang: &str,
+    api_key: &str,
+) -> Result<(CurrentWeather, String), String> {
+    let raw = weather_document(location, units, lang, Mode::Json, api_key).await?;
+    match serde_json::from_str::<CurrentWeather>(&raw) {
+        Ok(mut current) => {
+            current.units = units.parse().unwrap_or_default();
+            Ok((current, raw))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Fetch current weather once and tell how old it is, which matters if it was served from
+/// a cache (see module `cache`).
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`, `units`, `lang`, `api_key`: see `weather()`
+/// #### Return value
+/// - ⇒ `Ok(Cached<CurrentWeather>)`: weather information together with its age and whether it
+///   is outdated data of a `DiskCache` served because *OpenWeatherMap* is unavailable
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+pub async fn weather_cached(
+    location: &str,
+    units: &str,
+    lang: &str,
+    api_key: &str,
+) -> Result<cache::Cached<CurrentWeather>, String> {
+    let url = weather_url(location, units, lang, api_key);
+    let cached = fetch::spawn(move || fetch::get_cached(&url, true)).await?;
+    match serde_json::from_str::<CurrentWeather>(&cached.value) {
+        Ok(mut current) => {
+            current.units = units.parse().unwrap_or_default();
+            Ok(cached.map(|_| current))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Fetch current weather once in XML format and parse it into the same nested struct
+/// `weather()` returns.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`, `units`, `lang`, `api_key`: see `weather()`
+/// #### Return value
+/// - ⇒ `Ok(CurrentWeather)`: weather information in a nested struct called `CurrentWeather`
+/// - ⇒ `Err(String)`: Error message about any occured http or xml issue
+pub async fn weather_xml(
+    location: &str,
+    units: &str,
+    lang: &str,
+    api_key: &str,
+) -> Result<CurrentWeather, String> {
+    xml::parse(&weather_document(location, units, lang, Mode::Xml, api_key).await?)
+}
+
 /// synchronous functions
 pub mod blocking {
     use super::*;
@@ -181,6 +387,7 @@ pub mod blocking {
     /// - ⇒ `Err(String)`: Error message about any occured http or json issue
     ///         - e.g. `401 Unauthorized` if your API key is invalid
     ///         - some json parser error message if response from OpenWeatherMap could not be parsed
+    #[allow(clippy::doc_overindented_list_items)]
     pub fn weather(
         location: &str,
         units: &str,
@@ -190,4 +397,139 @@ pub mod blocking {
         // wait for result
         executor::block_on(super::weather(location, units, lang, api_key))
     }
+
+    /// Fetches solar irradiance (GHI, DNI and DHI) for a day once and returns it.
+    /// #### Parameters
+    /// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+    /// - `date`: Day in format `YYYY-MM-DD`:
+    ///     - a day in the past to get historical data
+    ///     - today to get current data
+    ///     - a day in the future (up to 15 days) to get a forecast
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    /// #### Return value
+    /// - ⇒ `Ok(SolarIrradiance)`: irradiance information in a nested struct called `SolarIrradiance`
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn solar_irradiance(
+        location: &str,
+        date: &str,
+        api_key: &str,
+    ) -> Result<SolarIrradiance, String> {
+        executor::block_on(super::solar_irradiance(location, date, api_key))
+    }
+
+    /// Fetches the predicted energy output of a solar panel installation for a day once and returns it.
+    /// #### Parameters
+    /// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+    /// - `date`: Day in format `YYYY-MM-DD` (today or up to 15 days in the future)
+    /// - `panel`: Installation parameters (capacity, tilt and azimuth)
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    /// #### Return value
+    /// - ⇒ `Ok(SolarPanelEnergy)`: energy prediction in a nested struct called `SolarPanelEnergy`
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn solar_panel_energy(
+        location: &str,
+        date: &str,
+        panel: &SolarPanel,
+        api_key: &str,
+    ) -> Result<SolarPanelEnergy, String> {
+        executor::block_on(super::solar_panel_energy(location, date, panel, api_key))
+    }
+
+    /// Fetches road risk reports for every waypoint of a route once and returns them.
+    /// #### Parameters
+    /// - `route`: waypoints with the time they will be passed
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    /// #### Return value
+    /// - ⇒ `Ok(Vec<RoadRisk>)`: one report per waypoint
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn road_risk(route: &[RouteWaypoint], api_key: &str) -> Result<Vec<RoadRisk>, String> {
+        executor::block_on(super::road_risk(route, api_key))
+    }
+
+    /// Fetches current Fire Weather Index once and returns it.
+    /// #### Parameters
+    /// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    /// #### Return value
+    /// - ⇒ `Ok(FireWeather)`: Fire Weather Index in a nested struct called `FireWeather`
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn fire_weather(location: &str, api_key: &str) -> Result<FireWeather, String> {
+        executor::block_on(super::fire_weather(location, api_key))
+    }
+
+    /// Fetches daily Fire Weather Index forecast once and returns it.
+    /// #### Parameters
+    /// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    /// #### Return value
+    /// - ⇒ `Ok(FireWeather)`: Fire Weather Index forecast in a nested struct called `FireWeather`
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn fire_weather_forecast(location: &str, api_key: &str) -> Result<FireWeather, String> {
+        executor::block_on(super::fire_weather_forecast(location, api_key))
+    }
+
+    /// Fetches current weather once as unparsed document in the given format and returns it.
+    /// #### Parameters
+    /// - `location`, `units`, `lang`, `api_key`: see `weather()`
+    /// - `mode`: format of the document (JSON, XML or HTML)
+    /// #### Return value
+    /// - ⇒ `Ok(String)`: the document as sent by *OpenWeatherMap*
+    /// - ⇒ `Err(String)`: Error message about any occured http issue
+    pub fn weather_document(
+        location: &str,
+        units: &str,
+        lang: &str,
+        mode: Mode,
+        api_key: &str,
+    ) -> Result<String, String> {
+        executor::block_on(super::weather_document(
+            location, units, lang, mode, api_key,
+        ))
+    }
+
+    /// Fetches current weather once and returns it parsed together with the unparsed JSON response.
+    /// #### Parameters
+    /// - `location`, `units`, `lang`, `api_key`: see `weather()`
+    /// #### Return value
+    /// - ⇒ `Ok((CurrentWeather, String))`: parsed weather information and the JSON response it was parsed from
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn weather_raw(
+        location: &str,
+        units: &str,
+        lang: &str,
+        api_key: &str,
+    ) -> Result<(CurrentWeather, String), String> {
+        executor::block_on(super::weather_raw(location, units, lang, api_key))
+    }
+
+    /// Fetches current weather once and returns it together with its age (see module `cache`).
+    /// #### Parameters
+    /// - `location`, `units`, `lang`, `api_key`: see `weather()`
+    /// #### Return value
+    /// - ⇒ `Ok(Cached<CurrentWeather>)`: weather information together with its age and whether it
+    ///   is outdated data of a `DiskCache` served because *OpenWeatherMap* is unavailable
+    /// - ⇒ `Err(String)`: Error message about any occured http or json issue
+    pub fn weather_cached(
+        location: &str,
+        units: &str,
+        lang: &str,
+        api_key: &str,
+    ) -> Result<cache::Cached<CurrentWeather>, String> {
+        executor::block_on(super::weather_cached(location, units, lang, api_key))
+    }
+
+    /// Fetches current weather once in XML format and returns it parsed into `CurrentWeather`.
+    /// #### Parameters
+    /// - `location`, `units`, `lang`, `api_key`: see `weather()`
+    /// #### Return value
+    /// - ⇒ `Ok(CurrentWeather)`: weather information in a nested struct called `CurrentWeather`
+    /// - ⇒ `Err(String)`: Error message about any occured http or xml issue
+    pub fn weather_xml(
+        location: &str,
+        units: &str,
+        lang: &str,
+        api_key: &str,
+    ) -> Result<CurrentWeather, String> {
+        executor::block_on(super::weather_xml(location, units, lang, api_key))
+    }
 }
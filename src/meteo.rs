@@ -0,0 +1,136 @@
+use crate::{CurrentWeather, Main, Units};
+
+// Magnus formula coefficients over water (Sonntag 1990)
+const MAGNUS_B: f64 = 17.62;
+const MAGNUS_C: f64 = 243.12;
+
+/// Saturation vapour pressure over water at `t` °C, hPa.
+fn saturation_vapour_pressure(t: f64) -> f64 {
+    6.112 * (MAGNUS_B * t / (MAGNUS_C + t)).exp()
+}
+
+/// Dew point at `t` °C and `rh` % relative humidity, °C.
+fn dew_point(t: f64, rh: f64) -> f64 {
+    let gamma = (rh.max(1e-3) / 100.0).ln() + MAGNUS_B * t / (MAGNUS_C + t);
+    MAGNUS_C * gamma / (MAGNUS_B - gamma)
+}
+
+/// Heat index at `t` °F and `rh` % relative humidity after the
+/// [NWS algorithm](https://www.wpc.ncep.noaa.gov/html/heatindex_equation.shtml), °F.
+fn heat_index(t: f64, rh: f64) -> f64 {
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return simple;
+    }
+    let mut hi = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+    }
+    hi
+}
+
+impl Main {
+    /// Dew point in the temperature unit of `units`.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn dew_point(&self, units: Units) -> f64 {
+        units.celsius_to_units(dew_point(units.to_celsius(self.temp), self.humidity))
+    }
+
+    /// Absolute humidity, g/m³.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn absolute_humidity(&self, units: Units) -> f64 {
+        let t = units.to_celsius(self.temp);
+        // water vapour partial pressure (Pa) divided by its specific gas constant and the absolute temperature
+        saturation_vapour_pressure(t) * self.humidity / 100.0 * 100.0 / (461.5 * (t + 273.15))
+            * 1000.0
+    }
+
+    /// Heat index (felt temperature in hot and humid air) in the temperature unit of `units`.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn heat_index(&self, units: Units) -> f64 {
+        let f = Units::Imperial.celsius_to_units(units.to_celsius(self.temp));
+        units.celsius_to_units(Units::Imperial.to_celsius(heat_index(f, self.humidity)))
+    }
+
+    /// Humidex (Canadian felt temperature) in the temperature unit of `units`.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn humidex(&self, units: Units) -> f64 {
+        let t = units.to_celsius(self.temp);
+        let td = dew_point(t, self.humidity) + 273.15;
+        let e = 6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / td)).exp();
+        units.celsius_to_units(t + 0.5555 * (e - 10.0))
+    }
+
+    /// Estimated height of the cumulus cloud base above ground from the spread between
+    /// temperature and dew point, meter.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn cloud_base(&self, units: Units) -> f64 {
+        let t = units.to_celsius(self.temp);
+        (125.0 * (t - dew_point(t, self.humidity))).max(0.0)
+    }
+}
+
+impl CurrentWeather {
+    /// Dew point in the temperature unit of the report.
+    pub fn dew_point(&self) -> f64 {
+        self.main.dew_point(self.units)
+    }
+
+    /// Absolute humidity, g/m³.
+    pub fn absolute_humidity(&self) -> f64 {
+        self.main.absolute_humidity(self.units)
+    }
+
+    /// Heat index (felt temperature in hot and humid air) in the temperature unit of the report.
+    pub fn heat_index(&self) -> f64 {
+        self.main.heat_index(self.units)
+    }
+
+    /// Humidex (Canadian felt temperature) in the temperature unit of the report.
+    pub fn humidex(&self) -> f64 {
+        self.main.humidex(self.units)
+    }
+
+    /// Estimated height of the cumulus cloud base above ground, meter (see `Main::cloud_base()`).
+    pub fn cloud_base(&self) -> f64 {
+        self.main.cloud_base(self.units)
+    }
+
+    /// Wind chill (felt temperature in cold and windy air) in the temperature unit of the
+    /// report after the North American formula.
+    /// `None` if the temperature is above 10 °C or the wind is below 4.8 km/h where the
+    /// formula is not defined.
+    pub fn wind_chill(&self) -> Option<f64> {
+        let units = self.units;
+        let t = units.to_celsius(self.main.temp);
+        let v = units.to_meter_per_second(self.wind.speed) * 3.6;
+        if t > 10.0 || v <= 4.8 {
+            return None;
+        }
+        let p = v.powf(0.16);
+        Some(units.celsius_to_units(13.12 + 0.6215 * t - 11.37 * p + 0.3965 * t * p))
+    }
+
+    /// Apparent temperature after Steadman (version of the Australian Bureau of Meteorology,
+    /// without solar radiation) in the temperature unit of the report.
+    pub fn apparent_temperature(&self) -> f64 {
+        let units = self.units;
+        let t = units.to_celsius(self.main.temp);
+        let ws = units.to_meter_per_second(self.wind.speed);
+        let e = self.main.humidity / 100.0 * 6.105 * (17.27 * t / (237.7 + t)).exp();
+        units.celsius_to_units(t + 0.33 * e - 0.70 * ws - 4.00)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{CurrentWeather, Length, Main, Pressure, Temperature, Units};
+
+// temperature lapse rate of the standard atmosphere, K/m
+const LAPSE_RATE: f64 = 0.0065;
+// exponent g·M/(R·L) of the barometric formula
+const EXPONENT: f64 = 5.257;
+
+/// Change of pressure between two samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PressureTendency {
+    /// Pressure rose by at least the threshold
+    Rising,
+    /// Pressure changed by less than the threshold
+    Steady,
+    /// Pressure fell by at least the threshold
+    Falling,
+}
+
+impl PressureTendency {
+    /// Threshold used by `Pressure::tendency()`, hPa (change within three hours as used
+    /// in synoptic reports).
+    pub const THRESHOLD: f64 = 1.0;
+
+    /// Tendency of a pressure change.
+    /// #### Parameters
+    /// - `change`: current minus previous pressure, hPa
+    /// - `threshold`: smallest change in hPa which is not considered steady
+    pub fn from_change(change: f64, threshold: f64) -> PressureTendency {
+        if change >= threshold {
+            PressureTendency::Rising
+        } else if change <= -threshold {
+            PressureTendency::Falling
+        } else {
+            PressureTendency::Steady
+        }
+    }
+}
+
+impl Pressure {
+    /// Reduces this station pressure to sea level after the barometric formula.
+    /// #### Parameters
+    /// - `elevation`: height of the station above sea level
+    /// - `temperature`: air temperature at the station
+    pub fn to_sea_level(self, elevation: Length, temperature: Temperature) -> Pressure {
+        let lh = LAPSE_RATE * elevation.meter();
+        Pressure::from_hectopascal(
+            self.hectopascal() * (1.0 - lh / (temperature.kelvin() + lh)).powf(-EXPONENT),
+        )
+    }
+
+    /// Tendency since a `previous` sample with a change of at least
+    /// `PressureTendency::THRESHOLD` hPa considered rising or falling.
+    pub fn tendency(self, previous: Pressure) -> PressureTendency {
+        PressureTendency::from_change(
+            self.hectopascal() - previous.hectopascal(),
+            PressureTendency::THRESHOLD,
+        )
+    }
+}
+
+impl Main {
+    /// Pressure at sea level: `sea_level` if reported, otherwise `grnd_level` (or `pressure`
+    /// without ground level either) reduced to sea level from `elevation`.
+    /// #### Parameters
+    /// - `elevation`: height of the location above sea level
+    /// - `units`: unit system the weather report was fetched in
+    pub fn sea_level_pressure(&self, elevation: Length, units: Units) -> Pressure {
+        match (self.sea_level, self.grnd_level) {
+            (Some(sea_level), _) => Pressure::from_hectopascal(sea_level),
+            (None, Some(grnd_level)) => Pressure::from_hectopascal(grnd_level)
+                .to_sea_level(elevation, self.temperature(units)),
+            (None, None) => self.pressure_quantity(),
+        }
+    }
+
+    /// Estimated height of the location above sea level from the difference between
+    /// `sea_level` and `grnd_level`.
+    /// `None` if one of them is missing.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn altitude(&self, units: Units) -> Option<Length> {
+        let ratio = self.sea_level? / self.grnd_level?;
+        let t = self.temperature(units).kelvin();
+        Some(Length::from_meter(
+            t * (ratio.powf(1.0 / EXPONENT) - 1.0) / LAPSE_RATE,
+        ))
+    }
+
+    /// Tendency of `pressure` since a `previous` report.
+    pub fn pressure_tendency(&self, previous: &Main) -> PressureTendency {
+        self.pressure_quantity()
+            .tendency(previous.pressure_quantity())
+    }
+}
+
+impl CurrentWeather {
+    /// Pressure at sea level (see `Main::sea_level_pressure()`).
+    /// #### Parameters
+    /// - `elevation`: height of the location above sea level
+    pub fn sea_level_pressure(&self, elevation: Length) -> Pressure {
+        self.main.sea_level_pressure(elevation, self.units)
+    }
+
+    /// Estimated height of the location above sea level (see `Main::altitude()`).
+    pub fn altitude(&self) -> Option<Length> {
+        self.main.altitude(self.units)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::{CurrentWeather, Main, Units, Wind};
+
+/// Temperature independent of a unit system
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Temperature {
+    kelvin: f64,
+}
+
+impl Temperature {
+    /// Temperature of `value` given in the temperature unit of `units`.
+    pub fn new(value: f64, units: Units) -> Temperature {
+        Temperature::from_celsius(units.to_celsius(value))
+    }
+
+    /// Temperature of `kelvin` K.
+    pub fn from_kelvin(kelvin: f64) -> Temperature {
+        Temperature { kelvin }
+    }
+
+    /// Temperature of `celsius` °C.
+    pub fn from_celsius(celsius: f64) -> Temperature {
+        Temperature::from_kelvin(celsius + 273.15)
+    }
+
+    /// Temperature of `fahrenheit` °F.
+    pub fn from_fahrenheit(fahrenheit: f64) -> Temperature {
+        Temperature::from_celsius((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    /// Value in the temperature unit of `units`.
+    pub fn value(&self, units: Units) -> f64 {
+        units.celsius_to_units(self.celsius())
+    }
+
+    /// Value in K.
+    pub fn kelvin(&self) -> f64 {
+        self.kelvin
+    }
+
+    /// Value in °C.
+    pub fn celsius(&self) -> f64 {
+        self.kelvin - 273.15
+    }
+
+    /// Value in °F.
+    pub fn fahrenheit(&self) -> f64 {
+        self.celsius() * 9.0 / 5.0 + 32.0
+    }
+}
+
+/// Speed independent of a unit system
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Speed {
+    meter_per_second: f64,
+}
+
+impl Speed {
+    /// Speed of `value` given in the speed unit of `units`.
+    pub fn new(value: f64, units: Units) -> Speed {
+        Speed::from_meter_per_second(units.to_meter_per_second(value))
+    }
+
+    /// Speed of `meter_per_second` m/s.
+    pub fn from_meter_per_second(meter_per_second: f64) -> Speed {
+        Speed { meter_per_second }
+    }
+
+    /// Speed of `kilometer_per_hour` km/h.
+    pub fn from_kilometer_per_hour(kilometer_per_hour: f64) -> Speed {
+        Speed::from_meter_per_second(kilometer_per_hour / 3.6)
+    }
+
+    /// Speed of `mile_per_hour` mi/h.
+    pub fn from_mile_per_hour(mile_per_hour: f64) -> Speed {
+        Speed::from_meter_per_second(mile_per_hour * 0.44704)
+    }
+
+    /// Speed of `knot` kn.
+    pub fn from_knot(knot: f64) -> Speed {
+        Speed::from_meter_per_second(knot * 1852.0 / 3600.0)
+    }
+
+    /// Value in the speed unit of `units`.
+    pub fn value(&self, units: Units) -> f64 {
+        units.meter_per_second_to_units(self.meter_per_second)
+    }
+
+    /// Value in m/s.
+    pub fn meter_per_second(&self) -> f64 {
+        self.meter_per_second
+    }
+
+    /// Value in km/h.
+    pub fn kilometer_per_hour(&self) -> f64 {
+        self.meter_per_second * 3.6
+    }
+
+    /// Value in mi/h.
+    pub fn mile_per_hour(&self) -> f64 {
+        self.meter_per_second / 0.44704
+    }
+
+    /// Value in kn.
+    pub fn knot(&self) -> f64 {
+        self.meter_per_second * 3600.0 / 1852.0
+    }
+}
+
+const HECTOPASCAL_PER_INCH_OF_MERCURY: f64 = 33.8639;
+const HECTOPASCAL_PER_MILLIMETER_OF_MERCURY: f64 = 1.333224;
+
+/// Pressure (reported in hPa in all unit systems)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Pressure {
+    hectopascal: f64,
+}
+
+impl Pressure {
+    /// Pressure of `hectopascal` hPa.
+    pub fn from_hectopascal(hectopascal: f64) -> Pressure {
+        Pressure { hectopascal }
+    }
+
+    /// Pressure of `kilopascal` kPa.
+    pub fn from_kilopascal(kilopascal: f64) -> Pressure {
+        Pressure::from_hectopascal(kilopascal * 10.0)
+    }
+
+    /// Pressure of `inch_of_mercury` inHg.
+    pub fn from_inch_of_mercury(inch_of_mercury: f64) -> Pressure {
+        Pressure::from_hectopascal(inch_of_mercury * HECTOPASCAL_PER_INCH_OF_MERCURY)
+    }
+
+    /// Pressure of `millimeter_of_mercury` mmHg.
+    pub fn from_millimeter_of_mercury(millimeter_of_mercury: f64) -> Pressure {
+        Pressure::from_hectopascal(millimeter_of_mercury * HECTOPASCAL_PER_MILLIMETER_OF_MERCURY)
+    }
+
+    /// Value in hPa.
+    pub fn hectopascal(&self) -> f64 {
+        self.hectopascal
+    }
+
+    /// Value in Pa.
+    pub fn pascal(&self) -> f64 {
+        self.hectopascal * 100.0
+    }
+
+    /// Value in kPa.
+    pub fn kilopascal(&self) -> f64 {
+        self.hectopascal / 10.0
+    }
+
+    /// Value in inHg.
+    pub fn inch_of_mercury(&self) -> f64 {
+        self.hectopascal / HECTOPASCAL_PER_INCH_OF_MERCURY
+    }
+
+    /// Value in mmHg.
+    pub fn millimeter_of_mercury(&self) -> f64 {
+        self.hectopascal / HECTOPASCAL_PER_MILLIMETER_OF_MERCURY
+    }
+}
+
+/// Length (visibility is reported in meters in all unit systems)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Length {
+    meter: f64,
+}
+
+impl Length {
+    /// Length of `meter` m.
+    pub fn from_meter(meter: f64) -> Length {
+        Length { meter }
+    }
+
+    /// Length of `mile` mi.
+    pub fn from_mile(mile: f64) -> Length {
+        Length::from_meter(mile * 1609.344)
+    }
+
+    /// Length of `foot` ft.
+    pub fn from_foot(foot: f64) -> Length {
+        Length::from_meter(foot * 0.3048)
+    }
+
+    /// Value in m.
+    pub fn meter(&self) -> f64 {
+        self.meter
+    }
+
+    /// Value in km.
+    pub fn kilometer(&self) -> f64 {
+        self.meter / 1000.0
+    }
+
+    /// Value in mi.
+    pub fn mile(&self) -> f64 {
+        self.meter / 1609.344
+    }
+
+    /// Value in ft.
+    pub fn foot(&self) -> f64 {
+        self.meter / 0.3048
+    }
+}
+
+impl Main {
+    /// `temp` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn temperature(&self, units: Units) -> Temperature {
+        Temperature::new(self.temp, units)
+    }
+
+    /// `feels_like` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn feels_like_temperature(&self, units: Units) -> Temperature {
+        Temperature::new(self.feels_like, units)
+    }
+
+    /// `temp_min` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn min_temperature(&self, units: Units) -> Temperature {
+        Temperature::new(self.temp_min, units)
+    }
+
+    /// `temp_max` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn max_temperature(&self, units: Units) -> Temperature {
+        Temperature::new(self.temp_max, units)
+    }
+
+    /// `pressure` as unit-safe quantity.
+    pub fn pressure_quantity(&self) -> Pressure {
+        Pressure::from_hectopascal(self.pressure)
+    }
+
+    fn convert(&mut self, from: Units, to: Units) {
+        for t in [
+            &mut self.temp,
+            &mut self.feels_like,
+            &mut self.temp_min,
+            &mut self.temp_max,
+        ] {
+            *t = Temperature::new(*t, from).value(to);
+        }
+    }
+}
+
+impl Wind {
+    /// `speed` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn speed_quantity(&self, units: Units) -> Speed {
+        Speed::new(self.speed, units)
+    }
+
+    /// `gust` as unit-safe quantity.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn gust_quantity(&self, units: Units) -> Option<Speed> {
+        Some(Speed::new(self.gust?, units))
+    }
+
+    fn convert(&mut self, from: Units, to: Units) {
+        self.speed = Speed::new(self.speed, from).value(to);
+        self.gust = self.gust.map(|g| Speed::new(g, from).value(to));
+    }
+}
+
+impl CurrentWeather {
+    /// `main.temp` as unit-safe quantity in the unit system of the report.
+    pub fn temperature(&self) -> Temperature {
+        self.main.temperature(self.units)
+    }
+
+    /// `main.feels_like` as unit-safe quantity in the unit system of the report.
+    pub fn feels_like_temperature(&self) -> Temperature {
+        self.main.feels_like_temperature(self.units)
+    }
+
+    /// `main.temp_min` as unit-safe quantity in the unit system of the report.
+    pub fn min_temperature(&self) -> Temperature {
+        self.main.min_temperature(self.units)
+    }
+
+    /// `main.temp_max` as unit-safe quantity in the unit system of the report.
+    pub fn max_temperature(&self) -> Temperature {
+        self.main.max_temperature(self.units)
+    }
+
+    /// `wind.speed` as unit-safe quantity in the unit system of the report.
+    pub fn wind_speed(&self) -> Speed {
+        self.wind.speed_quantity(self.units)
+    }
+
+    /// `wind.gust` as unit-safe quantity in the unit system of the report.
+    pub fn wind_gust(&self) -> Option<Speed> {
+        self.wind.gust_quantity(self.units)
+    }
+
+    /// `visibility` as unit-safe quantity.
+    pub fn visibility_quantity(&self) -> Option<Length> {
+        Some(Length::from_meter(self.visibility? as f64))
+    }
+
+    /// Re-expresses this report in another unit system without calling the API again.
+    /// #### Parameters
+    /// - `units`: unit system to convert into
+    /// #### Return value
+    /// - copy of the report with temperatures and wind speeds in `units` (and `units` set
+    ///   accordingly)
+    pub fn convert_to(&self, units: Units) -> CurrentWeather {
+        let mut converted = self.clone();
+        converted.main.convert(self.units, units);
+        converted.wind.convert(self.units, units);
+        converted.units = units;
+        converted
+    }
+}
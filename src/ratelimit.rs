@@ -0,0 +1,369 @@
+//! Client-side rate limiting to stay within the call quotas of an *OpenWeatherMap*
+//! subscription plan (see [pricing](https://openweathermap.org/price)).
+//!
+//! Once a limiter is enabled, every request of this crate which is not answered from a cache
+//! (including the polling threads of `init()`) takes a token from it. If no token is left,
+//! the request waits up to the limiter's maximum waiting time and fails with an error
+//! message that `RateLimitError::from_message()` turns back into a `RateLimitError`
+//! (the functions of this crate report all errors as `String`). The polling thread of `init()`
+//! reports the error and retries once the limiter allows it again (or ends if `poll_mins`
+//! is 0).
+//!
+//! The per minute quota is a token bucket refilled continuously while the per month quota
+//! counts the calls of the calendar month (UTC). A `persistent()` limiter keeps both in a
+//! file, so that calls made before a restart still count.
+//!
+//! ```no_run
+//! use openweathermap::ratelimit::{self, Plan, RateLimiter, RateLimitError};
+//! use std::time::Duration;
+//!
+//! let limiter = RateLimiter::for_plan(Plan::Free)
+//!     .with_max_wait(Duration::ZERO)
+//!     .persistent("ratelimit.json")
+//!     .unwrap();
+//! ratelimit::enable(limiter);
+//! match openweathermap::blocking::weather("Berlin,DE", "metric", "en", "<APIKEY>") {
+//!     Ok(current) => println!("{}", current.main.temp),
+//!     Err(e) => match RateLimitError::from_message(&e) {
+//!         Some(limited) => println!("try again in {:?}", limited.retry_after),
+//!         None => println!("{}", e),
+//!     },
+//! }
+//! ```
+
+use crate::datetime::{civil_from_days, days_from_civil};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Subscription plans of the *OpenWeatherMap* professional collections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Plan {
+    /// 60 calls/minute, 1,000,000 calls/month
+    Free,
+    /// 600 calls/minute, 10,000,000 calls/month
+    Startup,
+    /// 3,000 calls/minute, 100,000,000 calls/month
+    Developer,
+    /// 30,000 calls/minute, 1,000,000,000 calls/month
+    Professional,
+    /// 100,000 calls/minute, 3,000,000,000 calls/month
+    Expert,
+}
+
+impl Plan {
+    /// Calls allowed per minute.
+    pub fn per_minute(&self) -> u64 {
+        match self {
+            Plan::Free => 60,
+            Plan::Startup => 600,
+            Plan::Developer => 3_000,
+            Plan::Professional => 30_000,
+            Plan::Expert => 100_000,
+        }
+    }
+
+    /// Calls allowed per month.
+    pub fn per_month(&self) -> u64 {
+        match self {
+            Plan::Free => 1_000_000,
+            Plan::Startup => 10_000_000,
+            Plan::Developer => 100_000_000,
+            Plan::Professional => 1_000_000_000,
+            Plan::Expert => 3_000_000_000,
+        }
+    }
+}
+
+/// Error of a request refused by the rate limiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitError {
+    /// Time until the next call will be allowed (`RateLimitError::NEVER` if the quota does
+    /// not allow any further call)
+    pub retry_after: Duration,
+}
+
+const MESSAGE: &str = "rate limit exceeded, retry after ";
+
+impl RateLimitError {
+    /// Longest time to wait, reported if a quota of zero never allows another call
+    /// (`u64::MAX` milliseconds, so that it survives the error message).
+    pub const NEVER: Duration = Duration::from_millis(u64::MAX);
+
+    fn new(retry_after: Duration) -> RateLimitError {
+        RateLimitError {
+            retry_after: retry_after.min(RateLimitError::NEVER),
+        }
+    }
+}
+
+impl RateLimitError {
+    /// Recovers the error from the error message a request of this crate failed with.
+    /// `None` if the request failed for another reason.
+    pub fn from_message(message: &str) -> Option<RateLimitError> {
+        let millis: u128 = message
+            .strip_prefix(MESSAGE)?
+            .strip_suffix(" ms")?
+            .parse()
+            .ok()?;
+        Some(RateLimitError::new(Duration::from_millis(
+            millis.min(u64::MAX as u128) as u64,
+        )))
+    }
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{} ms", MESSAGE, self.retry_after.as_millis())
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+/// Bucket refilled continuously up to its capacity
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+}
+
+impl Bucket {
+    fn new(capacity: u64, per_second: f64) -> Bucket {
+        Bucket {
+            capacity: capacity as f64,
+            tokens: capacity as f64,
+            per_second,
+        }
+    }
+
+    fn refill(&mut self, seconds: f64) {
+        self.tokens = (self.tokens + seconds * self.per_second).min(self.capacity);
+    }
+
+    /// Time until the next token is available.
+    fn wait(&self) -> Duration {
+        match self.tokens >= 1.0 {
+            true => Duration::ZERO,
+            // a quota of zero never refills
+            false => Duration::try_from_secs_f64((1.0 - self.tokens) / self.per_second)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+/// Calls of a calendar month (UTC)
+struct Month {
+    quota: u64,
+    /// Year and month the calls were made in
+    month: (i64, i64),
+    calls: u64,
+}
+
+/// Current time, unix, UTC.
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Year and month of a unix time.
+fn month_of(unix: f64) -> (i64, i64) {
+    let (year, month, _) = civil_from_days((unix / 86400.0).floor() as i64);
+    (year, month)
+}
+
+impl Month {
+    /// Starts counting again if a new month has begun since the last call.
+    fn roll(&mut self, unix: f64) {
+        let month = month_of(unix);
+        if month != self.month {
+            self.month = month;
+            self.calls = 0;
+        }
+    }
+
+    /// Time until the next call is allowed.
+    fn wait(&self, unix: f64) -> Duration {
+        if self.calls < self.quota {
+            return Duration::ZERO;
+        }
+        if self.quota == 0 {
+            return Duration::MAX;
+        }
+        let (year, month) = match self.month {
+            (year, 12) => (year + 1, 1),
+            (year, month) => (year, month + 1),
+        };
+        let next = days_from_civil(year, month, 1) as f64 * 86400.0;
+        Duration::try_from_secs_f64(next - unix).unwrap_or(Duration::ZERO)
+    }
+}
+
+struct State {
+    minute: Bucket,
+    month: Month,
+    refilled: Instant,
+}
+
+/// State of the buckets as saved by a `persistent()` limiter
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    /// Month in format `YYYY-MM`
+    month: String,
+    /// Calls within `month`
+    calls: u64,
+    /// Tokens left of the per minute quota
+    minute_tokens: f64,
+    /// Time of saving, unix, UTC
+    saved: f64,
+}
+
+/// Token bucket rate limiter which can be shared between threads (clones share the same
+/// buckets)
+#[derive(Clone)]
+pub struct RateLimiter {
+    max_wait: Duration,
+    state: Arc<Mutex<State>>,
+    path: Option<PathBuf>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing bursts of up to `per_minute` calls and waiting up to one
+    /// minute for a token.
+    /// #### Parameters
+    /// - `per_minute`: calls allowed per minute
+    /// - `per_month`: calls allowed per calendar month (UTC)
+    pub fn new(per_minute: u64, per_month: u64) -> RateLimiter {
+        RateLimiter {
+            max_wait: Duration::from_secs(60),
+            state: Arc::new(Mutex::new(State {
+                minute: Bucket::new(per_minute, per_minute as f64 / 60.0),
+                month: Month {
+                    quota: per_month,
+                    month: month_of(now()),
+                    calls: 0,
+                },
+                refilled: Instant::now(),
+            })),
+            path: None,
+        }
+    }
+
+    /// Creates a limiter for the quotas of `plan`.
+    pub fn for_plan(plan: Plan) -> RateLimiter {
+        RateLimiter::new(plan.per_minute(), plan.per_month())
+    }
+
+    /// Sets the maximum time a request waits for a token before it fails
+    /// (`Duration::ZERO` fails immediately).
+    pub fn with_max_wait(mut self, max_wait: Duration) -> RateLimiter {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Keeps the state of the buckets in the JSON file `path` and continues the state already
+    /// stored there, so that calls made before a restart still count. The file is written
+    /// after every call.
+    pub fn persistent<P: AsRef<Path>>(mut self, path: P) -> Result<RateLimiter, String> {
+        if path.as_ref().exists() {
+            let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let saved: Saved = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let (year, month) = state.month.month;
+            if saved.month == format!("{:04}-{:02}", year, month) {
+                state.month.calls = saved.calls;
+            }
+            state.minute.tokens = saved.minute_tokens.min(state.minute.capacity);
+            state.minute.refill((now() - saved.saved).max(0.0));
+            state.refilled = Instant::now();
+        }
+        self.path = Some(path.as_ref().to_path_buf());
+        Ok(self)
+    }
+
+    /// Takes a token or returns the time until the next one is available.
+    fn take(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let seconds = state.refilled.elapsed().as_secs_f64();
+        let unix = now();
+        state.refilled = Instant::now();
+        state.minute.refill(seconds);
+        state.month.roll(unix);
+        match state.minute.wait().max(state.month.wait(unix)) {
+            Duration::ZERO => {
+                state.minute.tokens -= 1.0;
+                state.month.calls += 1;
+                if let Some(path) = &self.path {
+                    // failing to save the state must not block requests
+                    save(path, &state, unix).unwrap_or(());
+                }
+                Ok(())
+            }
+            wait => Err(wait),
+        }
+    }
+
+    /// Takes a token for one call, waiting for it up to the maximum waiting time.
+    pub fn acquire(&self) -> Result<(), RateLimitError> {
+        let mut waited = Duration::ZERO;
+        loop {
+            match self.take() {
+                Ok(()) => return Ok(()),
+                Err(wait) if waited.saturating_add(wait) <= self.max_wait => {
+                    thread::sleep(wait);
+                    waited += wait;
+                }
+                Err(wait) => return Err(RateLimitError::new(wait)),
+            }
+        }
+    }
+
+    /// Number of calls which can be made right now (limited by the per minute and the
+    /// per month quota).
+    pub fn available(&self) -> u64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let seconds = state.refilled.elapsed().as_secs_f64();
+        let minute =
+            (state.minute.tokens + seconds * state.minute.per_second).min(state.minute.capacity);
+        state.month.roll(now());
+        let month = state.month.quota.saturating_sub(state.month.calls);
+        (minute.max(0.0) as u64).min(month)
+    }
+}
+
+/// Writes the state of the buckets to `path` replacing its content at once.
+fn save(path: &Path, state: &State, unix: f64) -> Result<(), String> {
+    let (year, month) = state.month.month;
+    let saved = Saved {
+        month: format!("{:04}-{:02}", year, month),
+        calls: state.month.calls,
+        minute_tokens: state.minute.tokens,
+        saved: unix,
+    };
+    let json = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, json).map_err(|e| e.to_string())?;
+    fs::rename(&temp, path).map_err(|e| e.to_string())
+}
+
+static LIMITER: Mutex<Option<RateLimiter>> = Mutex::new(None);
+
+/// Limits all following requests of this crate by `limiter`.
+pub fn enable(limiter: RateLimiter) {
+    *LIMITER.lock().unwrap_or_else(|e| e.into_inner()) = Some(limiter);
+}
+
+/// Stops limiting requests.
+pub fn disable() {
+    *LIMITER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Limiter currently enabled.
+pub fn current() -> Option<RateLimiter> {
+    LIMITER.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
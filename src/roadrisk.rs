@@ -0,0 +1,110 @@
+use crate::fetch::{post_async, API};
+use serde::{Deserialize, Serialize};
+
+/// Point of a route at which the road risk shall be evaluated
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct RouteWaypoint {
+    /// geo location, latitude
+    pub lat: f64,
+    /// geo location, longitude
+    pub lon: f64,
+    /// Time when the point will be passed, unix, UTC
+    pub dt: i64,
+}
+
+/// Request body of the Road Risk API
+#[derive(Serialize, Debug)]
+struct Track {
+    track: Vec<RouteWaypoint>,
+}
+
+/// Weather at a waypoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoadWeather {
+    /// Temperature. Unit: Kelvin
+    pub temp: f64,
+    /// Wind speed, meter/sec
+    pub wind_speed: f64,
+    /// Wind direction, degrees (meteorological)
+    pub wind_deg: f64,
+    /// Precipitation intensity, mm/h
+    pub precipitation_intensity: Option<f64>,
+    /// Dew point. Unit: Kelvin
+    pub dew_point: f64,
+}
+
+/// National weather alert at a waypoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoadAlert {
+    /// Name of the alert source
+    pub sender_name: String,
+    /// Alert event name
+    pub event: String,
+    /// Severity of the alert: `1` (green) to `4` (red)
+    pub event_level: u8,
+}
+
+/// State of the road surface at a waypoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoadState {
+    /// Road state:
+    /// - `0`: no report
+    /// - `1`: dry
+    /// - `2`: moist
+    /// - `3`: moist and chemically treated
+    /// - `4`: wet
+    /// - `5`: wet and chemically treated
+    /// - `6`: ice
+    /// - `7`: frost
+    /// - `8`: snow
+    /// - `9`: snow/ice watch
+    /// - `10`: snow/ice warning
+    /// - `11`: wet above freezing
+    /// - `12`: wet below freezing
+    /// - `13`: absorption
+    /// - `14`: absorption at dewpoint
+    /// - `15`: dew
+    /// - `16`: black ice warning
+    /// - `17`: other
+    /// - `18`: slush
+    pub state: u8,
+    /// Road surface temperature. Unit: Kelvin
+    pub temp: f64,
+}
+
+/// Road risk report for a single waypoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoadRisk {
+    /// Time of the waypoint, unix, UTC
+    pub dt: i64,
+    /// geo location as `[longitude, latitude]`
+    pub coord: [f64; 2],
+    /// weather at the waypoint
+    pub weather: Option<RoadWeather>,
+    /// national weather alerts at the waypoint
+    #[serde(default)]
+    pub alerts: Vec<RoadAlert>,
+    /// state of the road surface
+    pub road: Option<RoadState>,
+}
+
+/// Fetch road risk reports for every waypoint of a route once.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `route`: waypoints with the time they will be passed
+/// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+/// #### Return value
+/// - ⇒ `Ok(Vec<RoadRisk>)`: one report per waypoint
+///   (see also [*OpenWeatherMap* documentation](https://openweathermap.org/api/road-risk) for details)
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+///     - some json parser error message if response from OpenWeatherMap could not be parsed
+pub async fn road_risk(route: &[RouteWaypoint], api_key: &str) -> Result<Vec<RoadRisk>, String> {
+    post_async(
+        format!("{}/data/2.5/roadrisk?appid={}", API, api_key),
+        Track {
+            track: route.to_vec(),
+        },
+    )
+    .await
+}
@@ -0,0 +1,175 @@
+use crate::coordinates;
+use crate::fetch::{get_async, API};
+use serde::{Deserialize, Serialize};
+
+/// Solar irradiance components, W/m²
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Irradiance {
+    /// Global Horizontal Irradiance
+    pub ghi: f64,
+    /// Direct Normal Irradiance
+    pub dni: f64,
+    /// Diffuse Horizontal Irradiance
+    pub dhi: f64,
+}
+
+/// Irradiance accumulated over a whole day, Wh/m²
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DailyIrradiance {
+    /// Irradiance under clear sky conditions
+    pub clear_sky: Irradiance,
+    /// Irradiance considering the cloud coverage
+    pub cloudy_sky: Irradiance,
+}
+
+/// Irradiance of a single hour, W/m²
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HourlyIrradiance {
+    /// Hour of the day in local time (0-23)
+    pub hour: u8,
+    /// Irradiance under clear sky conditions
+    pub clear_sky: Irradiance,
+    /// Irradiance considering the cloud coverage
+    pub cloudy_sky: Irradiance,
+}
+
+/// Daily and hourly irradiance values
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IrradianceData {
+    /// vector with one item of irradiance accumulated over the day
+    pub daily: Vec<DailyIrradiance>,
+    /// irradiance for every hour of the day
+    pub hourly: Vec<HourlyIrradiance>,
+}
+
+/// Solar irradiance report for a single day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SolarIrradiance {
+    /// geo location, latitude
+    pub lat: f64,
+    /// geo location, longitude
+    pub lon: f64,
+    /// Date of the report in format `YYYY-MM-DD`
+    pub date: String,
+    /// Timezone in format `±HH:MM`
+    pub tz: String,
+    /// Sunrise time, local time in format `YYYY-MM-DDTHH:MM:SS`
+    pub sunrise: String,
+    /// Sunset time, local time in format `YYYY-MM-DDTHH:MM:SS`
+    pub sunset: String,
+    /// irradiance values
+    pub irradiance: IrradianceData,
+}
+
+/// Solar panel installation to predict the energy output for
+#[derive(Debug, Clone, Copy)]
+pub struct SolarPanel {
+    /// Peak power of the installation, kW
+    pub capacity: f64,
+    /// Tilt of the panels from horizontal, degrees
+    pub tilt: f64,
+    /// Direction the panels are facing, degrees (meteorological, `180` is south)
+    pub azimuth: f64,
+}
+
+/// Energy output of a single hour, kWh
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HourlyEnergy {
+    /// Hour of the day in local time (0-23)
+    pub hour: u8,
+    /// Energy output under clear sky conditions
+    pub clear_sky: f64,
+    /// Energy output considering the cloud coverage
+    pub cloudy_sky: f64,
+}
+
+/// Energy output accumulated over a whole day, kWh
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DailyEnergy {
+    /// Energy output under clear sky conditions
+    pub clear_sky: f64,
+    /// Energy output considering the cloud coverage
+    pub cloudy_sky: f64,
+}
+
+/// Daily and hourly energy output values
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnergyData {
+    /// vector with one item of energy output accumulated over the day
+    pub daily: Vec<DailyEnergy>,
+    /// energy output for every hour of the day
+    pub hourly: Vec<HourlyEnergy>,
+}
+
+/// Solar panel energy prediction for a single day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SolarPanelEnergy {
+    /// geo location, latitude
+    pub lat: f64,
+    /// geo location, longitude
+    pub lon: f64,
+    /// Date of the prediction in format `YYYY-MM-DD`
+    pub date: String,
+    /// Timezone in format `±HH:MM`
+    pub tz: String,
+    /// Sunrise time, local time in format `YYYY-MM-DDTHH:MM:SS`
+    pub sunrise: String,
+    /// Sunset time, local time in format `YYYY-MM-DDTHH:MM:SS`
+    pub sunset: String,
+    /// energy output values
+    pub energy: EnergyData,
+}
+
+/// Fetch solar irradiance (GHI, DNI and DHI) for a day once.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+/// - `date`: Day in format `YYYY-MM-DD`:
+///     - a day in the past to get historical data
+///     - today to get current data
+///     - a day in the future (up to 15 days) to get a forecast
+/// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+/// #### Return value
+/// - ⇒ `Ok(SolarIrradiance)`: irradiance information in a nested struct called `SolarIrradiance`
+///   (see also [*OpenWeatherMap* documentation](https://openweathermap.org/api/solar-radiation) for details)
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+///     - a message if `location` is not a geographical coordinate
+pub async fn solar_irradiance(
+    location: &str,
+    date: &str,
+    api_key: &str,
+) -> Result<SolarIrradiance, String> {
+    let (lat, lon) = coordinates(location)?;
+    get_async(format!(
+        "{}/energy/1.0/solar/data?lat={}&lon={}&date={}&appid={}",
+        API, lat, lon, date, api_key
+    ))
+    .await
+}
+
+/// Fetch the predicted energy output of a solar panel installation for a day once.
+/// Returns the result in a *future*.
+/// #### Parameters
+/// - `location`: geographical coordinate given by comma separated latitude and longitude (e.g. `"52.5244,13.4105"`)
+/// - `date`: Day in format `YYYY-MM-DD` (today or up to 15 days in the future)
+/// - `panel`: Installation parameters (capacity, tilt and azimuth)
+/// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+/// #### Return value
+/// - ⇒ `Ok(SolarPanelEnergy)`: energy prediction in a nested struct called `SolarPanelEnergy`
+/// - ⇒ `Err(String)`: Error message about any occured http or json issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+///     - a message if `location` is not a geographical coordinate
+pub async fn solar_panel_energy(
+    location: &str,
+    date: &str,
+    panel: &SolarPanel,
+    api_key: &str,
+) -> Result<SolarPanelEnergy, String> {
+    let (lat, lon) = coordinates(location)?;
+    get_async(format!(
+        "{}/energy/1.0/solar/panel_data?lat={}&lon={}&date={}&capacity={}&tilt={}&azimuth={}&appid={}",
+        API, lat, lon, date, panel.capacity, panel.tilt, panel.azimuth, api_key
+    ))
+    .await
+}
@@ -0,0 +1,294 @@
+//! Publish measurements of your own weather stations to *OpenWeatherMap*
+//! (see [Weather Stations API 3.0](https://openweathermap.org/stations)).
+//!
+//! ```no_run
+//! use openweathermap::stations::{NewStation, Stations};
+//!
+//! let stations = Stations::new("<APIKEY>");
+//! let station = stations
+//!     .create(&NewStation {
+//!         external_id: "SF_TEST001".to_string(),
+//!         name: "San Francisco Test Station".to_string(),
+//!         latitude: 37.76,
+//!         longitude: -122.43,
+//!         altitude: Some(150.0),
+//!     })
+//!     .unwrap();
+//! println!("registered station {}", station.id);
+//! ```
+
+use crate::fetch::{parse, request, API};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Station to register or to update
+#[derive(Serialize, Debug, Clone)]
+pub struct NewStation {
+    /// Your own ID of the station
+    pub external_id: String,
+    /// Name of the station
+    pub name: String,
+    /// geo location, latitude
+    pub latitude: f64,
+    /// geo location, longitude
+    pub longitude: f64,
+    /// Altitude of the station, meter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<f64>,
+}
+
+/// Registered station
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Station {
+    /// Internal ID of the station
+    #[serde(alias = "ID")]
+    pub id: String,
+    /// Your own ID of the station
+    pub external_id: String,
+    /// Name of the station
+    pub name: String,
+    /// geo location, latitude
+    pub latitude: f64,
+    /// geo location, longitude
+    pub longitude: f64,
+    /// Altitude of the station, meter
+    pub altitude: Option<f64>,
+    /// Time of the station registration
+    pub created_at: Option<String>,
+    /// Time of the last station update
+    pub updated_at: Option<String>,
+    /// Internal parameter
+    pub rank: Option<u64>,
+}
+
+/// Single measurement of a station.
+/// All units are metric: °C, m/s, hPa, %, mm.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Measurement {
+    /// Internal ID of the station
+    pub station_id: String,
+    /// Time of measurement, unix, UTC
+    pub dt: i64,
+    /// Air temperature, °C
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Wind speed, m/s
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_speed: Option<f64>,
+    /// Wind gust, m/s
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<f64>,
+    /// Wind direction, degrees (meteorological)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_deg: Option<f64>,
+    /// Atmospheric pressure, hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f64>,
+    /// Humidity, %
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<f64>,
+    /// Rain volume for the last 1 hour, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain_1h: Option<f64>,
+    /// Rain volume for the last 6 hours, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain_6h: Option<f64>,
+    /// Rain volume for the last 24 hours, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain_24h: Option<f64>,
+    /// Snow volume for the last 1 hour, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow_1h: Option<f64>,
+    /// Snow volume for the last 6 hours, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow_6h: Option<f64>,
+    /// Snow volume for the last 24 hours, mm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow_24h: Option<f64>,
+}
+
+/// Aggregation period of measurements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// per minute
+    Minute,
+    /// per hour
+    Hour,
+    /// per day
+    Day,
+}
+
+impl Aggregation {
+    fn code(&self) -> &'static str {
+        match self {
+            Aggregation::Minute => "m",
+            Aggregation::Hour => "h",
+            Aggregation::Day => "d",
+        }
+    }
+}
+
+/// Aggregated values of a measured quantity
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    /// Minimum value
+    pub min: Option<f64>,
+    /// Maximum value
+    pub max: Option<f64>,
+    /// Average value
+    pub average: Option<f64>,
+    /// Number of aggregated measurements
+    pub weight: Option<f64>,
+}
+
+/// Aggregated wind values
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AggregatedWind {
+    /// Wind direction, degrees (meteorological)
+    pub deg: Option<f64>,
+    /// Wind speed, m/s
+    pub speed: Option<f64>,
+}
+
+/// Aggregated precipitation values
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AggregatedPrecipitation {
+    /// Rain volume, mm
+    pub rain: Option<f64>,
+    /// Snow volume, mm
+    pub snow: Option<f64>,
+}
+
+/// Measurements of a station aggregated over one period
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AggregatedMeasurement {
+    /// Aggregation period code (`m`, `h` or `d`)
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Start of the period, unix, UTC
+    pub date: i64,
+    /// Internal ID of the station
+    pub station_id: String,
+    /// Air temperature, °C
+    pub temp: Option<Aggregate>,
+    /// Humidity, %
+    pub humidity: Option<Aggregate>,
+    /// Atmospheric pressure, hPa
+    pub pressure: Option<Aggregate>,
+    /// Wind
+    pub wind: Option<AggregatedWind>,
+    /// Precipitation
+    pub precipitation: Option<AggregatedPrecipitation>,
+}
+
+/// Access to the stations registered with an API key.
+pub struct Stations {
+    url: String,
+    api_key: String,
+}
+
+impl Stations {
+    /// Access the stations at *OpenWeatherMap*.
+    /// #### Parameters
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    pub fn new(api_key: &str) -> Stations {
+        Stations::with_base_url(API, api_key)
+    }
+
+    /// Access the stations at another server (e.g. a local mock server for testing).
+    /// #### Parameters
+    /// - `base_url`: URL of the server without trailing slash (e.g. `"http://127.0.0.1:8080"`)
+    /// - `api_key`: Your API key which you can get [here](https://openweathermap.org/price)
+    pub fn with_base_url(base_url: &str, api_key: &str) -> Stations {
+        Stations {
+            url: format!("{}/data/3.0", base_url),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Registers a new station and returns it including its internal ID.
+    pub fn create(&self, station: &NewStation) -> Result<Station, String> {
+        parse(request(
+            Method::POST,
+            &format!("{}/stations?appid={}", self.url, self.api_key),
+            Some(station),
+        )?)
+    }
+
+    /// Lists all registered stations.
+    pub fn list(&self) -> Result<Vec<Station>, String> {
+        parse(request::<()>(
+            Method::GET,
+            &format!("{}/stations?appid={}", self.url, self.api_key),
+            None,
+        )?)
+    }
+
+    /// Gets a registered station by its internal ID.
+    pub fn get(&self, id: &str) -> Result<Station, String> {
+        parse(request::<()>(
+            Method::GET,
+            &format!("{}/stations/{}?appid={}", self.url, id, self.api_key),
+            None,
+        )?)
+    }
+
+    /// Updates a registered station and returns it.
+    pub fn update(&self, id: &str, station: &NewStation) -> Result<Station, String> {
+        parse(request(
+            Method::PUT,
+            &format!("{}/stations/{}?appid={}", self.url, id, self.api_key),
+            Some(station),
+        )?)
+    }
+
+    /// Deletes a registered station.
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        request::<()>(
+            Method::DELETE,
+            &format!("{}/stations/{}?appid={}", self.url, id, self.api_key),
+            None,
+        )
+        .map(|_| ())
+    }
+
+    /// Submits a batch of measurements of one or more stations.
+    pub fn send(&self, measurements: &[Measurement]) -> Result<(), String> {
+        request(
+            Method::POST,
+            &format!("{}/measurements?appid={}", self.url, self.api_key),
+            Some(&measurements),
+        )
+        .map(|_| ())
+    }
+
+    /// Queries the aggregated measurements of a station.
+    /// #### Parameters
+    /// - `id`: internal ID of the station
+    /// - `aggregation`: aggregation period
+    /// - `limit`: maximum number of returned periods
+    /// - `from`, `to`: time range, unix, UTC
+    pub fn measurements(
+        &self,
+        id: &str,
+        aggregation: Aggregation,
+        limit: u32,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<AggregatedMeasurement>, String> {
+        parse(request::<()>(
+            Method::GET,
+            &format!(
+                "{}/measurements?station_id={}&type={}&limit={}&from={}&to={}&appid={}",
+                self.url,
+                id,
+                aggregation.code(),
+                limit,
+                from,
+                to,
+                self.api_key
+            ),
+            None,
+        )?)
+    }
+}
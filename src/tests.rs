@@ -12,20 +12,88 @@ fn apikey() -> String {
     }
 }
 
+/// Serves the given `(status, body)` responses one per connection on a local port
+/// and returns its base URL plus a handle to collect the received requests.
+fn mock_server(
+    responses: Vec<(&'static str, &'static str)>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 4096];
+            // read header and as much body as announced by `content-length`
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                data.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&data).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|l| {
+                            l.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if data.len() >= end + 4 + length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            requests.push(String::from_utf8_lossy(&data).to_string());
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// Serializes the tests which send requests, because some of them enable process-wide
+/// settings (e.g. a rate limiter) which would affect the requests of the others.
+fn exclusive() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Opens a file from the `tests/fixtures` directory.
+fn fixture(name: &str) -> std::fs::File {
+    std::fs::File::open(format!(
+        "{}/tests/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+    .unwrap()
+}
+
 #[test]
 fn test_city() {
+    let _exclusive = exclusive();
     let w = blocking::weather("Munich,DE", "metric", "en", &apikey()).unwrap();
     assert_eq!(w.name, "Munich");
 }
 
 #[test]
 fn test_cityid() {
+    let _exclusive = exclusive();
     let w = blocking::weather("2950159", "metric", "en", &apikey()).unwrap();
     assert_eq!(w.name, "Berlin");
 }
 
 #[test]
 fn test_coordinate() {
+    let _exclusive = exclusive();
     let w = blocking::weather("52.5244,13.4105", "metric", "en", &apikey()).unwrap();
     assert_eq!(w.coord.lat, 52.5244);
     assert_eq!(w.coord.lon, 13.4105);
@@ -33,12 +101,14 @@ fn test_coordinate() {
 
 #[test]
 fn test_language() {
+    let _exclusive = exclusive();
     let w = blocking::weather("München,DE", "metric", "de", &apikey()).unwrap();
     assert_eq!(w.name, "München");
 }
 
 #[test]
 fn test_units() {
+    let _exclusive = exclusive();
     let w1 = blocking::weather("Berlin,DE", "metric", "en", &apikey()).unwrap();
     let w2 = blocking::weather("Berlin,DE", "imperial", "en", &apikey()).unwrap();
     let w3 = blocking::weather("Berlin,DE", "standard", "en", &apikey()).unwrap();
@@ -49,12 +119,14 @@ fn test_units() {
 
 #[test]
 fn test_apikey() {
+    let _exclusive = exclusive();
     let w = blocking::weather("Berlin,DE", "metric", "de", "");
     assert!(w.is_err());
 }
 
 #[test]
 fn test_cities() {
+    let _exclusive = exclusive();
     let mut rng = thread_rng();
     let max = 10;
     for _i in 0..=max {
@@ -64,6 +136,1066 @@ fn test_cities() {
     }
 }
 
+#[test]
+fn test_solar_irradiance_parse() {
+    let s: SolarIrradiance = serde_json::from_str(
+        r#"{"lat":52.5244,"lon":13.4105,"date":"2023-03-30","tz":"+02:00",
+            "sunrise":"2023-03-30T06:53:01","sunset":"2023-03-30T19:41:54",
+            "irradiance":{
+                "daily":[{"clear_sky":{"ghi":5211.1,"dni":7902.8,"dhi":1120.6},
+                          "cloudy_sky":{"ghi":2420.3,"dni":1680.9,"dhi":1410.2}}],
+                "hourly":[{"hour":12,"clear_sky":{"ghi":670.4,"dni":860.1,"dhi":102.8},
+                           "cloudy_sky":{"ghi":310.7,"dni":95.3,"dhi":240.5}}]}}"#,
+    )
+    .unwrap();
+    assert_eq!(s.irradiance.daily[0].clear_sky.ghi, 5211.1);
+    assert_eq!(s.irradiance.hourly[0].hour, 12);
+    assert_eq!(s.irradiance.hourly[0].cloudy_sky.dhi, 240.5);
+}
+
+#[test]
+fn test_solar_location() {
+    let _exclusive = exclusive();
+    assert!(blocking::solar_irradiance("Berlin,DE", "2023-03-30", &apikey()).is_err());
+    assert_eq!(coordinates("52.5244, 13.4105"), Ok(("52.5244", "13.4105")));
+}
+
+#[test]
+fn test_road_risk_parse() {
+    let r: Vec<RoadRisk> = serde_json::from_str(
+        r#"[{"dt":1602702000,"coord":[7.27,44.04],
+             "weather":{"temp":278.44,"wind_speed":2.27,"wind_deg":7,
+                        "precipitation_intensity":0.38,"dew_point":276.13},
+             "alerts":[{"sender_name":"METEO-FRANCE","event":"Moderate thunderstorm warning","event_level":2}],
+             "road":{"state":4,"temp":293.85}},
+            {"dt":1602702400,"coord":[7.37,45.04]}]"#,
+    )
+    .unwrap();
+    assert_eq!(r.len(), 2);
+    assert_eq!(r[0].alerts[0].event_level, 2);
+    assert_eq!(r[0].road.as_ref().unwrap().state, 4);
+    assert!(r[1].weather.is_none());
+    assert!(r[1].alerts.is_empty());
+}
+
+#[test]
+fn test_fire_weather_parse() {
+    let f: FireWeather = serde_json::from_str(
+        r#"{"coord":{"lon":13.4105,"lat":52.5244},
+            "list":[{"main":{"fwi":12.07},"danger_rating":{"description":"Moderate","value":2},"dt":1701075600}]}"#,
+    )
+    .unwrap();
+    assert_eq!(f.list[0].main.fwi, 12.07);
+    assert_eq!(f.list[0].danger_rating.value, 2);
+    assert_eq!(f.list[0].danger_rating.description, "Moderate");
+}
+
+#[test]
+fn test_tiles() {
+    let tile = tiles::Tile::from_coord(52.5244, 13.4105, 10);
+    assert_eq!(
+        tile,
+        tiles::Tile {
+            z: 10,
+            x: 550,
+            y: 335
+        }
+    );
+    assert_eq!(
+        tiles::url(tiles::Layer::Clouds, &tile, "KEY"),
+        "http://tile.openweathermap.org/map/clouds_new/10/550/335.png?appid=KEY"
+    );
+    let options = tiles::Options {
+        date: Some(1552861800),
+        opacity: Some(0.6),
+        ..Default::default()
+    };
+    assert_eq!(
+        tiles::url2(tiles::Layer2::Temperature, &tile, &options, "KEY"),
+        "http://maps.openweathermap.org/maps/2.0/weather/TA2/10/550/335?appid=KEY&date=1552861800&opacity=0.6"
+    );
+    assert_eq!(
+        tiles::Tile::from_coord(90.0, 180.0, 0),
+        tiles::Tile { z: 0, x: 0, y: 0 }
+    );
+    let options = tiles::Options {
+        palette: Some("0:FF0000;10:00FF00".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        tiles::url2(tiles::Layer2::Temperature, &tile, &options, "KEY"),
+        "http://maps.openweathermap.org/maps/2.0/weather/TA2/10/550/335?appid=KEY&palette=0%3AFF0000%3B10%3A00FF00"
+    );
+    let tile = tiles::Tile::from_coord(-85.0511, 180.0, 255);
+    assert_eq!(tile.z, tiles::MAX_ZOOM);
+    assert_eq!(tile.x, (1 << tiles::MAX_ZOOM) - 1);
+}
+
+#[test]
+fn test_stations() {
+    let _exclusive = exclusive();
+    let (url, server) = mock_server(vec![
+        (
+            "201 Created",
+            r#"{"ID":"583436dd9643a9000196b8d6","updated_at":"2016-11-22T12:15:25.967Z","created_at":"2016-11-22T12:15:25.967Z","user_id":"557066d0ff7a7e3897531d94","external_id":"SF_TEST001","name":"San Francisco Test Station","longitude":-122.43,"latitude":37.76,"altitude":150,"source_type":5}"#,
+        ),
+        ("204 No Content", ""),
+        (
+            "200 OK",
+            r#"[{"type":"h","date":1479817200,"station_id":"583436dd9643a9000196b8d6","temp":{"max":22.1,"min":20.3,"average":21.2,"weight":3},"humidity":{"average":87,"weight":3},"wind":{"deg":120,"speed":1.2},"pressure":{"min":1019,"max":1021,"average":1020,"weight":3},"precipitation":{}}]"#,
+        ),
+        (
+            "401 Unauthorized",
+            r#"{"cod":401,"message":"Invalid API key."}"#,
+        ),
+    ]);
+    let stations = stations::Stations::with_base_url(&url, "KEY");
+    let station = stations
+        .create(&stations::NewStation {
+            external_id: "SF_TEST001".to_string(),
+            name: "San Francisco Test Station".to_string(),
+            latitude: 37.76,
+            longitude: -122.43,
+            altitude: Some(150.0),
+        })
+        .unwrap();
+    assert_eq!(station.id, "583436dd9643a9000196b8d6");
+    stations
+        .send(&[stations::Measurement {
+            station_id: station.id.clone(),
+            dt: 1479817340,
+            temperature: Some(18.7),
+            ..Default::default()
+        }])
+        .unwrap();
+    let m = stations
+        .measurements(
+            &station.id,
+            stations::Aggregation::Hour,
+            100,
+            1469817340,
+            1479817340,
+        )
+        .unwrap();
+    assert_eq!(m[0].temp.as_ref().unwrap().average, Some(21.2));
+    assert_eq!(
+        stations.delete(&station.id),
+        Err("401 Unauthorized".to_string())
+    );
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST /data/3.0/stations?appid=KEY "));
+    assert!(requests[0].ends_with(r#""latitude":37.76,"longitude":-122.43,"altitude":150.0}"#));
+    assert!(requests[1].ends_with(
+        r#"[{"station_id":"583436dd9643a9000196b8d6","dt":1479817340,"temperature":18.7}]"#
+    ));
+    assert!(requests[2].starts_with(
+        "GET /data/3.0/measurements?station_id=583436dd9643a9000196b8d6&type=h&limit=100&from=1469817340&to=1479817340&appid=KEY "
+    ));
+    assert!(
+        requests[3].starts_with("DELETE /data/3.0/stations/583436dd9643a9000196b8d6?appid=KEY ")
+    );
+}
+
+#[test]
+fn test_agro() {
+    let _exclusive = exclusive();
+    let (url, server) = mock_server(vec![
+        (
+            "201 Created",
+            r#"{"id":"5aaa8052cbbbb5000b73ff66","geo_json":{"type":"Feature","properties":{},"geometry":{"type":"Polygon","coordinates":[[[-121.1958,37.6683],[-121.1779,37.6683],[-121.1958,37.6565],[-121.1958,37.6683]]]}},"name":"Field 1","center":[-121.1898,37.6644],"area":190.6343,"user_id":"557066d0ff7a7e3897531d94"}"#,
+        ),
+        (
+            "200 OK",
+            r#"{"dt":1522108800,"t10":281.96,"moisture":0.175,"t0":279.02}"#,
+        ),
+        (
+            "200 OK",
+            r#"[{"dt":1500940800,"type":"Landsat 8","dc":100,"cl":1.56,"sun":{"azimuth":126.742,"elevation":63.572},"image":{"truecolor":"http://api.agromonitoring.com/image/1.0/00059768a00/5aaa8052cbbbb5000b73ff66","ndvi":"http://api.agromonitoring.com/image/1.0/01059768a00/5aaa8052cbbbb5000b73ff66"},"tile":{"ndvi":"http://api.agromonitoring.com/tile/1.0/{z}/{x}/{y}/01059768a00/5aaa8052cbbbb5000b73ff66"},"stats":{"ndvi":"http://api.agromonitoring.com/stats/1.0/01059768a00/5aaa8052cbbbb5000b73ff66","evi":"http://api.agromonitoring.com/stats/1.0/02059768a00/5aaa8052cbbbb5000b73ff66"},"data":{}}]"#,
+        ),
+        (
+            "200 OK",
+            r#"{"dt":1485703465,"weather":[{"id":804,"main":"Clouds","description":"overcast clouds","icon":"04d"}],"main":{"temp":284.15,"pressure":1016,"humidity":93,"temp_min":284.15,"temp_max":284.15},"wind":{"speed":1.5,"deg":280},"clouds":{"all":90}}"#,
+        ),
+        (
+            "200 OK",
+            r#"[{"id":"5aaa8052cbbbb5000b73ff66","geo_json":{"type":"Feature","properties":{},"geometry":{"type":"Polygon","coordinates":[[[-121.1958,37.6683],[-121.1779,37.6683],[-121.1958,37.6565],[-121.1958,37.6683]]]}},"name":"Field 1","center":[-121.1898,37.6644],"area":190.6343,"user_id":"557066d0ff7a7e3897531d94"}]"#,
+        ),
+        ("204 No Content", ""),
+    ]);
+    let agro = agro::Agro::with_base_url(&url, "KEY");
+    let outline = [
+        Coord {
+            lat: 37.6683,
+            lon: -121.1958,
+        },
+        Coord {
+            lat: 37.6683,
+            lon: -121.1779,
+        },
+        Coord {
+            lat: 37.6565,
+            lon: -121.1958,
+        },
+    ];
+    let polygon = agro
+        .create(&agro::NewPolygon::new("Field 1", &outline))
+        .unwrap();
+    assert_eq!(polygon.center.lat, 37.6644);
+    assert_eq!(polygon.geo_json.geometry.coordinates[0].len(), 4);
+    assert_eq!(agro.soil(&polygon.id).unwrap().moisture, 0.175);
+    let images = agro.images(&polygon.id, 1500000000, 1510000000).unwrap();
+    assert_eq!(images[0].type_, "Landsat 8");
+    assert!(images[0]
+        .stats
+        .ndvi
+        .as_ref()
+        .unwrap()
+        .contains("/stats/1.0/"));
+    let weather = agro.weather(&polygon.id).unwrap();
+    assert_eq!(weather.main.temp, 284.15);
+    assert_eq!(weather.wind.deg, Some(280.0));
+    let polygons = agro.list().unwrap();
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0], polygon);
+    agro.delete(&polygon.id).unwrap();
+    let requests = server.join().unwrap();
+    assert!(requests[0].ends_with(
+        r#""coordinates":[[[-121.1958,37.6683],[-121.1779,37.6683],[-121.1958,37.6565],[-121.1958,37.6683]]]}}}"#
+    ));
+    assert!(requests[2].starts_with(
+        "GET /agro/1.0/image/search?start=1500000000&end=1510000000&polyid=5aaa8052cbbbb5000b73ff66&appid=KEY "
+    ));
+    assert!(
+        requests[3].starts_with("GET /agro/1.0/weather?polyid=5aaa8052cbbbb5000b73ff66&appid=KEY ")
+    );
+    assert!(requests[4].starts_with("GET /agro/1.0/polygons?appid=KEY "));
+    assert!(
+        requests[5].starts_with("DELETE /agro/1.0/polygons/5aaa8052cbbbb5000b73ff66?appid=KEY ")
+    );
+    // Agro keeps its own key even if a key pool is enabled
+    let (url, server) = mock_server(vec![("401 Unauthorized", r#"{"cod":401}"#)]);
+    let pool = keys::KeyPool::new(&["A"], keys::Rotation::RoundRobin);
+    keys::enable(pool.clone());
+    let refused = agro::Agro::with_base_url(&url, "KEY").list();
+    keys::disable();
+    assert_eq!(refused.unwrap_err(), "401 Unauthorized");
+    assert!(server.join().unwrap()[0].starts_with("GET /agro/1.0/polygons?appid=KEY "));
+    assert!(!pool.status()[0].invalid && pool.status()[0].calls == 0);
+}
+
+#[test]
+fn test_bulk_cities() {
+    let cities = bulk::cities_gz(fixture("city.list.json.gz")).unwrap();
+    assert_eq!(cities.len(), 10);
+    let munich = cities.iter().find(|c| c.id == 2867714).unwrap();
+    assert_eq!(munich.name, "München");
+    assert_eq!(munich.coord.lat, 48.137428);
+    assert_eq!(cities[0].stat.as_ref().unwrap().population, Some(3426354));
+    assert!(bulk::cities(fixture("city.list.json.gz")).is_err());
+}
+
+#[test]
+fn test_bulk_weather() {
+    let plain: Vec<CurrentWeather> = bulk::weather(fixture("weather.json"))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let gz: Vec<CurrentWeather> = bulk::weather_gz(fixture("weather.json.gz"))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(plain.len(), 2);
+    assert_eq!(gz.len(), 2);
+    assert_eq!(gz[1].id, 2867714);
+    assert_eq!(gz[1].rain.as_ref().unwrap().h1, Some(0.35));
+    assert!(bulk::weather("{}\n".as_bytes()).next().unwrap().is_err());
+}
+
+#[cfg(feature = "city-index")]
+#[test]
+fn test_city_index() {
+    let index = cities::CityIndex::from_gz(fixture("city.list.json.gz")).unwrap();
+    assert_eq!(index.search("Munchen", 1)[0].id, 2867714);
+    assert_eq!(index.search("MÜNCHEN,de", 1)[0].location(), "2867714");
+    assert_eq!(index.search("Muenchen", 1)[0].id, 2867714);
+    assert_eq!(index.search("zurich", 1)[0].name, "Zürich");
+    assert_eq!(index.search("Berln", 1)[0].id, 2950159);
+    assert_eq!(index.search("New", 5)[0].id, 5128581);
+    assert!(index.search("Munchen,AT", 1).is_empty());
+    assert_eq!(index.search("New York,NY,US", 1)[0].id, 5128581);
+    assert_eq!(index.search("new york, ny, us", 1)[0].id, 5128581);
+    assert!(index.search("New York,NJ,US", 1).is_empty());
+    let near = index
+        .nearest(&Coord {
+            lat: 48.2,
+            lon: 11.6,
+        })
+        .unwrap();
+    assert_eq!(near.id, 2867714);
+    let (city, km) = index.nearest_n(
+        &Coord {
+            lat: 52.52,
+            lon: 13.40,
+        },
+        2,
+    )[0];
+    assert_eq!(city.id, 2950159);
+    assert!(km < 1.0);
+    let mut compact = Vec::new();
+    index.write_compact(&mut compact).unwrap();
+    let restored = cities::CityIndex::from_compact(compact.as_slice()).unwrap();
+    assert_eq!(restored.cities().len(), index.cities().len());
+    assert_eq!(restored.get(2950159).unwrap().coord.lat, 52.524368);
+    assert_eq!(restored.search("Munchen", 1)[0].location(), "2867714");
+    let embedded = cities::CityIndex::embedded();
+    assert_eq!(embedded.search("Munchen,DE", 1)[0].id, 2867714);
+    assert!(std::ptr::eq(embedded, cities::CityIndex::embedded()));
+    assert!(cities::CityIndex::from_compact(&b"no index"[..]).is_err());
+}
+
+#[test]
+fn test_xml() {
+    let w = xml::parse(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <current>
+          <city id="2867714" name="München">
+            <coord lon="11.5755" lat="48.1374"></coord>
+            <country>DE</country>
+            <timezone>7200</timezone>
+            <sun rise="2021-10-18T05:30:42" set="2021-10-18T16:17:02"></sun>
+          </city>
+          <temperature value="9.1" min="7.8" max="10.2" unit="celsius"></temperature>
+          <feels_like value="7.6" unit="celsius"></feels_like>
+          <humidity value="87" unit="%"></humidity>
+          <pressure value="1021" unit="hPa"></pressure>
+          <wind>
+            <speed value="2.57" unit="m/s" name="Light breeze"></speed>
+            <gusts value="5.1"></gusts>
+            <direction value="230" code="SW" name="Southwest"></direction>
+          </wind>
+          <clouds value="90" name="overcast clouds"></clouds>
+          <visibility value="9000"></visibility>
+          <precipitation value="0.35" mode="rain" unit="1h"></precipitation>
+          <weather number="500" value="leichter Regen" icon="10d"></weather>
+          <lastupdate value="2021-10-18T11:03:49"></lastupdate>
+        </current>"#,
+    )
+    .unwrap();
+    assert_eq!(w.id, 2867714);
+    assert_eq!(w.name, "München");
+    assert_eq!(w.units, Units::Metric);
+    assert_eq!(w.coord.lat, 48.1374);
+    assert_eq!(w.sys.country.as_deref(), Some("DE"));
+    assert_eq!(w.sys.sunrise, Some(1634535042));
+    assert_eq!(w.dt, 1634555029);
+    assert_eq!(w.timezone, 7200);
+    assert_eq!(w.main.temp, 9.1);
+    assert_eq!(w.wind.gust, Some(5.1));
+    assert_eq!(w.rain.unwrap().h1, Some(0.35));
+    assert_eq!(w.weather[0].main, "Rain");
+    assert_eq!(w.weather[0].description, "leichter Regen");
+    assert!(xml::parse("<current></current>").is_err());
+    assert!(weather_url("Berlin,DE", "metric", "en", "KEY").contains("q=Berlin,DE&"));
+}
+
+#[test]
+fn test_round_trip() {
+    for w in bulk::weather(fixture("weather.json")) {
+        let w = w.unwrap();
+        let json = serde_json::to_string(&w).unwrap();
+        assert!(json.contains(r#""sys":{"type":2,"#));
+        assert_eq!(serde_json::from_str::<CurrentWeather>(&json).unwrap(), w);
+        assert_eq!(w.clone(), w);
+        assert!(!json.contains("_units"));
+        // the fixture is in metric units
+        let metric = CurrentWeather {
+            units: Units::Metric,
+            ..w
+        };
+        let json = serde_json::to_string(&metric).unwrap();
+        assert!(json.contains(r#""_units":"metric""#));
+        assert_eq!(
+            serde_json::from_str::<CurrentWeather>(&json).unwrap(),
+            metric
+        );
+        let imperial = metric.convert_to(Units::Imperial);
+        let json = serde_json::to_string(&imperial).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CurrentWeather>(&json).unwrap(),
+            imperial
+        );
+    }
+    let volume = Volume {
+        h1: Some(0.35),
+        h3: None,
+        extra: Default::default(),
+    };
+    let json = serde_json::to_string(&volume).unwrap();
+    assert_eq!(json, r#"{"1h":0.35,"3h":null}"#);
+    assert_eq!(serde_json::from_str::<Volume>(&json).unwrap(), volume);
+}
+
+#[test]
+fn test_edge_cases() {
+    let dir = format!("{}/tests/fixtures/current", env!("CARGO_MANIFEST_DIR"));
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let text = std::fs::read_to_string(&path).unwrap();
+        if let Err(e) = serde_json::from_str::<CurrentWeather>(&text) {
+            panic!("{}: {}", path.display(), e);
+        }
+        count += 1;
+    }
+    assert!(count >= 4);
+    let ocean: CurrentWeather = serde_json::from_reader(fixture("current/ocean.json")).unwrap();
+    assert!(ocean.sys.country.is_none());
+    assert!(ocean.visibility.is_none());
+    let polar: CurrentWeather =
+        serde_json::from_reader(fixture("current/polar_night.json")).unwrap();
+    assert!(polar.sys.sunrise.is_none() && polar.sys.sunset.is_none());
+    let calm: CurrentWeather =
+        serde_json::from_reader(fixture("current/calm_station.json")).unwrap();
+    assert!(calm.wind.deg.is_none());
+}
+
+#[test]
+fn test_extra_fields() {
+    let raw = r#"{"coord":{"lon":13.4105,"lat":52.5244},"weather":[{"id":800,"main":"Clear","description":"clear sky","icon":"01d","emoji":"☀"}],"base":"stations","main":{"temp":12.3,"feels_like":11.2,"temp_min":10.9,"temp_max":13.7,"pressure":1018,"humidity":71,"uv_index":3.2},"visibility":10000,"wind":{"speed":4.63,"deg":250},"clouds":{"all":0},"dt":1634555021,"sys":{"country":"DE","sunrise":1634535621,"sunset":1634573461},"timezone":7200,"id":2950159,"name":"Berlin","cod":200,"air":{"aqi":2}}"#;
+    let w: CurrentWeather = serde_json::from_str(raw).unwrap();
+    assert_eq!(w.extra["air"]["aqi"], 2);
+    assert_eq!(w.main.extra["uv_index"], 3.2);
+    assert_eq!(w.weather[0].extra["emoji"], "☀");
+    assert!(w.wind.extra.is_empty());
+    let json = serde_json::to_string(&w).unwrap();
+    assert!(json.contains(r#""uv_index":3.2"#));
+    assert_eq!(serde_json::from_str::<CurrentWeather>(&json).unwrap(), w);
+}
+
+#[test]
+fn test_condition() {
+    assert_eq!(Condition::from_id(502), Condition::HeavyRain);
+    assert_eq!(Condition::HeavyRain.id(), 502);
+    assert_eq!(Condition::from_id(999), Condition::Unknown(999));
+    assert_eq!(Condition::from_id(731).main(), "Dust");
+    assert_eq!(Condition::from_id(804).group(), Group::Clouds);
+    assert_eq!(Condition::from_id(804).description(), "overcast clouds");
+    assert_eq!(Condition::from_id(504).intensity(), Intensity::Extreme);
+    assert!(Condition::from_id(201).is_precipitation());
+    assert!(!Condition::from_id(211).is_precipitation());
+    assert!(Condition::from_id(611).is_freezing());
+    assert_eq!(Condition::Tornado.severity(), 4);
+    assert_eq!(Condition::HeavyThunderstorm.severity(), 4);
+    assert_eq!(Condition::ClearSky.severity(), 0);
+    assert!(Condition::LightRain.severity() < Condition::HeavyRain.severity());
+    let w = bulk::weather(fixture("weather.json"))
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(w.weather[0].condition(), Condition::BrokenClouds);
+    assert_eq!(w.weather[0].condition().main(), w.weather[0].main);
+}
+
+#[test]
+fn test_icon() {
+    let mut w = bulk::weather(fixture("weather.json"))
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        w.weather[0].icon_url(IconSize::X2),
+        "http://openweathermap.org/img/wn/10d@2x.png"
+    );
+    assert_eq!(
+        icon_url("01n", IconSize::X1),
+        "http://openweathermap.org/img/wn/01n.png"
+    );
+    assert!(w.weather[0].is_day());
+    assert_eq!(w.weather[0].emoji(), "🌧️");
+    assert_eq!(w.weather[0].nerd_font(), '\u{e308}');
+    assert_eq!(w.weather[0].weather_icons(), "wi-owm-day-500");
+    w.weather[0].icon = "10n".to_string();
+    assert!(w.weather[0].is_night());
+    assert_eq!(w.weather[0].nerd_font(), '\u{e325}');
+    assert_eq!(Condition::ClearSky.emoji(false), "🌙");
+    assert_eq!(Condition::Tornado.emoji(true), "🌪️");
+}
+
+#[test]
+fn test_datetime() {
+    let w = bulk::weather(fixture("weather.json"))
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(w.is_daytime(), Some(true));
+    assert_eq!(w.day_length(), Some(std::time::Duration::from_secs(37840)));
+    assert_eq!(
+        w.time_until_sunset(),
+        Some(std::time::Duration::from_secs(18440))
+    );
+    assert_eq!(w.time_until_sunrise(), None);
+    let polar: CurrentWeather =
+        serde_json::from_reader(fixture("current/polar_night.json")).unwrap();
+    assert_eq!(polar.is_daytime(), None);
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::Timelike;
+        assert_eq!(
+            w.chrono_dt_utc().unwrap().to_rfc3339(),
+            "2021-10-18T11:03:41+00:00"
+        );
+        assert_eq!(
+            w.chrono_dt_local().unwrap().to_rfc3339(),
+            "2021-10-18T13:03:41+02:00"
+        );
+        assert_eq!(w.chrono_sunset_local().unwrap().hour(), 18);
+        assert_eq!(w.chrono_offset().local_minus_utc(), 7200);
+    }
+    #[cfg(feature = "time")]
+    {
+        assert_eq!(w.time_dt_local().unwrap().hour(), 13);
+        assert_eq!(w.time_dt_utc().unwrap().hour(), 11);
+        assert_eq!(w.time_offset().whole_hours(), 2);
+        assert_eq!(w.time_sunrise_local().unwrap().unix_timestamp(), 1634535621);
+    }
+    let mut far = w.clone();
+    far.dt = i64::MAX;
+    #[cfg(feature = "chrono")]
+    assert_eq!(far.chrono_dt_utc(), None);
+    #[cfg(feature = "time")]
+    assert_eq!(far.time_dt_utc(), None);
+    // local time beyond the range of `time`
+    far.dt = 253402300799;
+    far.timezone = 3600;
+    #[cfg(feature = "time")]
+    assert_eq!(far.time_dt_local(), None);
+    // offsets out of range fall back to UTC
+    far.timezone = i64::from(i32::MAX) + 7200;
+    #[cfg(feature = "chrono")]
+    assert_eq!(far.chrono_offset().local_minus_utc(), 0);
+    #[cfg(feature = "time")]
+    assert_eq!(far.time_offset().whole_seconds(), 0);
+}
+
+/// Parses a `Main` report of the given temperature and humidity.
+fn main_report(temp: f64, humidity: f64) -> Main {
+    serde_json::from_value(serde_json::json!({
+        "temp": temp, "feels_like": temp, "temp_min": temp, "temp_max": temp,
+        "pressure": 1013.25, "humidity": humidity
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_meteo() {
+    let close = |a: f64, b: f64, e: f64| assert!((a - b).abs() < e, "{} != {}", a, b);
+    let m = main_report(20.0, 50.0);
+    close(m.dew_point(Units::Metric), 9.26, 0.05);
+    close(
+        main_report(293.15, 50.0).dew_point(Units::Standard),
+        282.41,
+        0.05,
+    );
+    close(
+        main_report(68.0, 50.0).dew_point(Units::Imperial),
+        48.67,
+        0.1,
+    );
+    close(m.absolute_humidity(Units::Metric), 8.63, 0.05);
+    close(
+        main_report(68.0, 50.0).absolute_humidity(Units::Imperial),
+        8.63,
+        0.05,
+    );
+    close(m.cloud_base(Units::Metric), 1343.0, 10.0);
+    close(
+        main_report(90.0, 70.0).heat_index(Units::Imperial),
+        105.9,
+        0.2,
+    );
+    close(
+        main_report(32.22, 70.0).heat_index(Units::Metric),
+        41.1,
+        0.2,
+    );
+    close(main_report(30.0, 40.0).humidex(Units::Metric), 33.9, 0.1);
+    assert_eq!(Units::Metric.name(), "metric");
+    assert_eq!("imperial".parse::<Units>(), Ok(Units::Imperial));
+    assert!("kelvin".parse::<Units>().is_err());
+
+    let mut w = bulk::weather(fixture("weather.json"))
+        .next()
+        .unwrap()
+        .unwrap();
+    w.units = Units::Metric;
+    w.main = main_report(-10.0, 80.0);
+    w.wind.speed = 20.0 / 3.6;
+    close(w.wind_chill().unwrap(), -17.9, 0.1);
+    w.wind.speed = 1.0;
+    assert!(w.wind_chill().is_none());
+    w.main = main_report(25.0, 60.0);
+    w.wind.speed = 2.0;
+    close(w.apparent_temperature(), 25.85, 0.05);
+    // the unit system of the report is used
+    let imperial = w.convert_to(Units::Imperial);
+    close(imperial.apparent_temperature(), 25.85 * 1.8 + 32.0, 0.1);
+}
+
+#[test]
+fn test_wind() {
+    let w = bulk::weather(fixture("weather.json"))
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    // 2.57 m/s from 230° gusting 5.1 m/s
+    assert_eq!(w.wind.beaufort(Units::Metric), Beaufort::LightBreeze);
+    assert_eq!(w.wind.beaufort(Units::Imperial), Beaufort::LightAir);
+    assert_eq!(
+        w.wind.gust_beaufort(Units::Metric),
+        Some(Beaufort::GentleBreeze)
+    );
+    assert_eq!(Beaufort::from_meter_per_second(40.0).force(), 12);
+    assert_eq!(Beaufort::Gale.description("de"), "stürmischer Wind");
+    assert_eq!(Beaufort::Gale.description("xx"), "gale");
+    assert_eq!(w.wind.compass(CompassPoints::P8), Some("SW"));
+    assert_eq!(w.wind.compass(CompassPoints::P16), Some("SW"));
+    assert_eq!(w.wind.compass(CompassPoints::P32), Some("SW"));
+    assert_eq!(compass(236.0, CompassPoints::P32), "SWbW");
+    assert_eq!(compass(355.0, CompassPoints::P16), "N");
+    assert_eq!(compass(-90.0, CompassPoints::P8), "W");
+    let (u, v) = w.wind.components().unwrap();
+    assert!((u - 1.969).abs() < 0.001 && (v - 1.652).abs() < 0.001);
+    assert!((w.wind.gust_factor().unwrap() - 1.984).abs() < 0.001);
+}
+
+#[test]
+fn test_quantity() {
+    let mut w = bulk::weather(fixture("weather.json"))
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    // fixture is in metric units
+    w.units = Units::Metric;
+    let t = w.main.temperature(w.units);
+    assert!((t.celsius() - 9.1).abs() < 1e-9);
+    assert!((t.kelvin() - 282.25).abs() < 1e-9);
+    assert!((Temperature::from_celsius(100.0).fahrenheit() - 212.0).abs() < 1e-9);
+    assert!((Temperature::new(32.0, Units::Imperial).celsius()).abs() < 1e-9);
+    assert!((Speed::from_knot(1.0).kilometer_per_hour() - 1.852).abs() < 1e-9);
+    assert!((Speed::new(10.0, Units::Imperial).meter_per_second() - 4.4704).abs() < 1e-9);
+    assert!((Length::from_mile(1.0).foot() - 5280.0).abs() < 1e-6);
+    assert_eq!(w.main.pressure_quantity().pascal(), w.main.pressure * 100.0);
+    let standard = w.convert_to(Units::Standard);
+    assert_eq!(standard.units, Units::Standard);
+    assert!((standard.main.temp - t.kelvin()).abs() < 1e-9);
+    assert_eq!(standard.wind, w.wind);
+    let imperial = standard.convert_to(Units::Imperial);
+    assert!((imperial.main.temp_max - w.main.max_temperature(w.units).fahrenheit()).abs() < 1e-9);
+    assert!((imperial.wind.speed - 5.749).abs() < 0.001);
+    let gust = imperial.wind.gust_quantity(imperial.units).unwrap();
+    assert!((gust.meter_per_second() - 5.1).abs() < 1e-9);
+    // accessors of the report use its own unit system
+    assert_eq!(w.temperature(), t);
+    assert!((imperial.temperature().celsius() - 9.1).abs() < 1e-9);
+    assert!((imperial.max_temperature().kelvin() - standard.main.temp_max).abs() < 1e-9);
+    assert!((imperial.wind_speed().meter_per_second() - 2.57).abs() < 1e-9);
+    assert!((imperial.wind_gust().unwrap().meter_per_second() - 5.1).abs() < 1e-9);
+    assert_eq!(imperial.beaufort(), w.wind.beaufort(Units::Metric));
+    assert!(
+        (Temperature::new(imperial.dew_point(), Units::Imperial).celsius() - w.dew_point()).abs()
+            < 1e-9
+    );
+    let back = imperial.convert_to(Units::Metric);
+    assert!((back.main.feels_like - w.main.feels_like).abs() < 1e-9);
+    assert_eq!(back.visibility_quantity(), w.visibility_quantity());
+}
+
+#[test]
+fn test_pressure() {
+    let close = |a: f64, b: f64, e: f64| assert!((a - b).abs() < e, "{} != {}", a, b);
+    let p = Pressure::from_hectopascal(1013.25);
+    close(p.inch_of_mercury(), 29.92, 0.01);
+    close(p.millimeter_of_mercury(), 760.0, 0.01);
+    close(p.kilopascal(), 101.325, 1e-9);
+    close(
+        Pressure::from_inch_of_mercury(29.92).hectopascal(),
+        1013.2,
+        0.1,
+    );
+    let mut m = main_report(15.0, 50.0);
+    assert_eq!(m.altitude(Units::Metric), None);
+    assert_eq!(
+        m.sea_level_pressure(Length::from_meter(500.0), Units::Metric),
+        p
+    );
+    m.sea_level = Some(1013.25);
+    m.grnd_level = Some(900.0);
+    let altitude = m.altitude(Units::Metric).unwrap();
+    close(altitude.meter(), 1010.8, 0.1);
+    m.sea_level = None;
+    close(
+        m.sea_level_pressure(altitude, Units::Metric).hectopascal(),
+        1013.25,
+        1e-6,
+    );
+    let previous = main_report(15.0, 50.0);
+    assert_eq!(m.pressure_tendency(&previous), PressureTendency::Steady);
+    m.pressure = 1010.0;
+    assert_eq!(m.pressure_tendency(&previous), PressureTendency::Falling);
+    assert_eq!(previous.pressure_tendency(&m), PressureTendency::Rising);
+    assert_eq!(
+        PressureTendency::from_change(0.8, 0.5),
+        PressureTendency::Rising
+    );
+}
+
+#[test]
+fn test_astro() {
+    let berlin = bulk::weather(fixture("weather.json"))
+        .next()
+        .unwrap()
+        .unwrap();
+    let times = berlin.sun_times();
+    // published times are 07:37 and 18:05 CEST
+    assert!((times.sunrise.unwrap() - 1634535420).abs() < 120);
+    assert!((times.sunset.unwrap() - 1634573100).abs() < 120);
+    let noon = astro::sun_position(&berlin.coord, times.solar_noon);
+    assert!((noon.azimuth - 180.0).abs() < 0.5);
+    assert!((noon.elevation - 27.8).abs() < 0.5);
+    let (dawn, dusk) = astro::twilight(&berlin.coord, berlin.dt, astro::Twilight::Civil).unwrap();
+    assert!(dawn < times.sunrise.unwrap() && dusk > times.sunset.unwrap());
+    assert!(
+        astro::sun_position(&berlin.coord, times.sunrise.unwrap())
+            .elevation
+            .abs()
+            < 1.0
+    );
+    let mut report = berlin.clone();
+    report.sys.sunrise = None;
+    report.fill_sun_times();
+    assert_eq!(report.sys.sunrise, times.sunrise);
+    assert_eq!(report.sys.sunset, berlin.sys.sunset);
+    // polar night in Tromsø on 2021-12-21
+    let tromso = Coord {
+        lat: 69.65,
+        lon: 18.96,
+    };
+    let winter = astro::sun_times(&tromso, 1640088000);
+    assert_eq!(winter.sunrise, None);
+    assert!(astro::twilight(&tromso, 1640088000, astro::Twilight::Civil).is_some());
+    assert!(astro::sun_position(&tromso, winter.solar_noon).elevation < 0.0);
+    // full moon on 2021-10-20 14:57 UTC
+    let moon = astro::moon(1634741820);
+    assert_eq!(moon.phase, astro::MoonPhase::FullMoon);
+    assert!(moon.illumination > 0.99);
+    assert_eq!(
+        astro::moon(1634741820 + 7 * 86400).phase,
+        astro::MoonPhase::LastQuarter
+    );
+}
+
+#[test]
+fn test_cache() {
+    let _exclusive = exclusive();
+    use cache::{Cache, Key};
+    let key = Key::from_url(
+        "http://api.openweathermap.org/data/2.5/weather?q=Berlin,DE&units=metric&lang=de&appid=KEY",
+    )
+    .unwrap();
+    assert_eq!(key.endpoint, "api.openweathermap.org/data/2.5/weather");
+    assert_eq!(key.location, "q=Berlin,DE");
+    assert_eq!((key.units.as_str(), key.lang.as_str()), ("metric", "de"));
+    // the server answers only once, so the second request must be served from the cache
+    let (url, server) = mock_server(vec![("200 OK", r#"{"value":1}"#)]);
+    let cache = Cache::new(Duration::from_secs(60), 1);
+    let url = format!("{}/data/2.5/weather?id=2950159&units=metric&appid=A", url);
+    for api_key in ["A", "B"] {
+        let response = cache::fetch(
+            Some(&cache),
+            None,
+            &url.replace("appid=A", &format!("appid={}", api_key)),
+            false,
+            || crate::fetch::get_text(&url).map_err(cache::Failure::Refused),
+        );
+        assert_eq!(response.unwrap().value, r#"{"value":1}"#);
+    }
+    assert_eq!(server.join().unwrap().len(), 1);
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+    // the oldest entry is dropped when full
+    let other = Key {
+        location: "id=2867714".to_string(),
+        ..key.clone()
+    };
+    cache.insert(key.clone(), "berlin".to_string());
+    cache.insert(other.clone(), "munich".to_string());
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&key), None);
+    assert_eq!(cache.get(&other), Some("munich".to_string()));
+    // expired entries are not served
+    let cache = Cache::new(Duration::from_millis(10), 10);
+    cache.insert(key.clone(), "berlin".to_string());
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(cache.get(&key), None);
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_disk_cache() {
+    use cache::{Cache, DiskCache, Failure};
+    let dir = std::env::temp_dir().join(format!(
+        "openweathermap-test-{}-{}",
+        std::process::id(),
+        thread_rng().gen::<u32>()
+    ));
+    let disk = DiskCache::new(&dir).unwrap();
+    let url = "http://api.openweathermap.org/data/2.5/weather?q=München,DE&units=metric&appid=A";
+    let fetched = cache::fetch(None, Some(&disk), url, true, || {
+        Ok::<_, Failure<String>>("{}".to_string())
+    });
+    assert_eq!(fetched.unwrap().age, Duration::ZERO);
+    // offline after a restart: outdated response is served and marked as stale
+    let disk = DiskCache::new(&dir).unwrap();
+    let memory = Cache::default();
+    let offline = || Err(Failure::Unavailable("offline".to_string()));
+    assert_eq!(
+        cache::fetch(Some(&memory), Some(&disk), url, false, offline),
+        Err("offline".to_string())
+    );
+    let unauthorized = || Err(Failure::Refused("401 Unauthorized".to_string()));
+    assert_eq!(
+        cache::fetch(Some(&memory), Some(&disk), url, true, unauthorized),
+        Err("401 Unauthorized".to_string())
+    );
+    let stale = cache::fetch(Some(&memory), Some(&disk), url, true, offline).unwrap();
+    assert_eq!((stale.value.as_str(), stale.stale), ("{}", true));
+    assert!(stale.age < Duration::from_secs(60));
+    assert!(memory.is_empty());
+    let other = url.replace("units=metric", "units=imperial");
+    assert_eq!(
+        cache::fetch(None, Some(&disk), &other, true, offline),
+        Err("offline".to_string())
+    );
+    // names of long keys are cut to fit into the file name limit
+    let long = format!("{}&{}", url, "x=0".repeat(200));
+    let ok = || Ok::<_, Failure<String>>("long".to_string());
+    cache::fetch(None, Some(&disk), &long, true, ok).unwrap();
+    let stale_long = cache::fetch(None, Some(&disk), &long, true, offline).unwrap();
+    assert_eq!(stale_long.value, "long");
+    assert!(std::fs::read_dir(&dir)
+        .unwrap()
+        .all(|e| e.unwrap().file_name().len() <= 255));
+    let current: CurrentWeather = serde_json::from_reader(fixture("current/ocean.json")).unwrap();
+    let cached = stale.map(|_| current.clone());
+    assert_eq!((cached.value, cached.stale), (current, true));
+    disk.clear().unwrap();
+    assert_eq!(
+        cache::fetch(None, Some(&disk), url, true, offline),
+        Err("offline".to_string())
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_ratelimit() {
+    use ratelimit::{Plan, RateLimitError, RateLimiter};
+    assert_eq!(
+        (Plan::Free.per_minute(), Plan::Free.per_month()),
+        (60, 1_000_000)
+    );
+    let limiter = RateLimiter::new(2, 1000).with_max_wait(Duration::ZERO);
+    assert_eq!(limiter.available(), 2);
+    assert!(limiter.clone().acquire().is_ok());
+    assert!(limiter.acquire().is_ok());
+    let e = limiter.acquire().unwrap_err();
+    assert!(e.retry_after > Duration::from_secs(29) && e.retry_after <= Duration::from_secs(30));
+    assert_eq!(
+        RateLimitError::from_message(&e.to_string()),
+        Some(RateLimitError {
+            retry_after: Duration::from_millis(e.retry_after.as_millis() as u64)
+        })
+    );
+    assert_eq!(RateLimitError::from_message("401 Unauthorized"), None);
+    // the monthly quota limits as well
+    let limiter = RateLimiter::new(60, 1).with_max_wait(Duration::ZERO);
+    assert!(limiter.acquire().is_ok());
+    let next_month = limiter.acquire().unwrap_err().retry_after;
+    assert!(next_month > Duration::ZERO && next_month <= Duration::from_secs(31 * 86400));
+    // calls made before a restart still count
+    let path = std::env::temp_dir().join(format!(
+        "openweathermap-ratelimit-{}-{}.json",
+        std::process::id(),
+        thread_rng().gen::<u32>()
+    ));
+    let limiter = RateLimiter::new(60, 2).persistent(&path).unwrap();
+    assert!(limiter.acquire().is_ok());
+    let restarted = RateLimiter::new(60, 2)
+        .with_max_wait(Duration::ZERO)
+        .persistent(&path)
+        .unwrap();
+    assert_eq!(restarted.available(), 1);
+    assert!(restarted.acquire().is_ok());
+    assert!(restarted.acquire().is_err());
+    std::fs::remove_file(path).unwrap();
+    let never = RateLimiter::new(0, 1000).with_max_wait(Duration::from_secs(1));
+    let e = never.acquire().unwrap_err();
+    assert_eq!(e.retry_after, RateLimitError::NEVER);
+    assert_eq!(RateLimitError::from_message(&e.to_string()), Some(e));
+    let never = RateLimiter::new(60, 0).with_max_wait(Duration::ZERO);
+    assert_eq!(
+        never.acquire().unwrap_err().retry_after,
+        RateLimitError::NEVER
+    );
+    // init() reports a refused request at once instead of waiting for the limiter
+    let _exclusive = exclusive();
+    let drained = RateLimiter::new(1, 1000).with_max_wait(Duration::ZERO);
+    drained.acquire().unwrap();
+    for (limiter, min_wait) in [
+        (drained, Duration::from_secs(59)),
+        (never, RateLimitError::NEVER),
+    ] {
+        ratelimit::enable(limiter);
+        let start = std::time::Instant::now();
+        let e = blocking::weather("Berlin,DE", "metric", "en", "KEY").unwrap_err();
+        ratelimit::disable();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(RateLimitError::from_message(&e).unwrap().retry_after >= min_wait);
+    }
+    // waiting for the next token (one every 10 ms)
+    let limiter = RateLimiter::new(6000, 1_000_000).with_max_wait(Duration::from_secs(1));
+    while limiter.available() > 0 {
+        limiter.acquire().unwrap();
+    }
+    let start = std::time::Instant::now();
+    assert!(limiter.acquire().is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(1));
+}
+
+#[test]
+fn test_usage() {
+    let _exclusive = exclusive();
+    use usage::Usage;
+    assert_eq!(usage::date(0), "1970-01-01");
+    assert_eq!(usage::date(1634555021), "2021-10-18");
+    assert_eq!(usage::date(951782400), "2000-02-29");
+    assert_eq!(usage::this_month(), usage::today()[..7]);
+    let path = std::env::temp_dir().join(format!(
+        "openweathermap-usage-{}-{}.json",
+        std::process::id(),
+        thread_rng().gen::<u32>()
+    ));
+    let counter = Usage::persistent(&path).unwrap();
+    let weather = "http://api.openweathermap.org/data/2.5/weather?q=Berlin&units=metric&appid=A";
+    counter.record_on("2024-05-31", weather).unwrap();
+    counter.record_on("2024-06-01", weather).unwrap();
+    counter
+        .record_on("2024-06-01", &weather.replace("appid=A", "appid=B"))
+        .unwrap();
+    counter
+        .record_on(
+            "2024-06-02",
+            "http://api.openweathermap.org/data/2.5/fwi?lat=1.0&lon=2.0&appid=A",
+        )
+        .unwrap();
+    // counters survive a restart
+    let report = Usage::persistent(&path).unwrap().report();
+    assert_eq!(report, counter.report());
+    assert_eq!(report.calls(), 4);
+    assert_eq!(report.month("2024-06").calls(), 3);
+    assert_eq!(report.month("2024-0").calls(), 0);
+    assert_eq!(report.day("2024-06-01").api_key("A").calls(), 1);
+    assert_eq!(
+        report
+            .endpoint("api.openweathermap.org/data/2.5/weather")
+            .calls(),
+        3
+    );
+    assert_eq!(report.api_key("A").month("2024-06").entries.len(), 2);
+    counter.clear().unwrap();
+    assert_eq!(Usage::persistent(&path).unwrap().report().calls(), 0);
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(Usage::new().report(), usage::UsageReport::default());
+    // only calls which got a response are counted
+    let get = |url: &str| reqwest::blocking::get(url);
+    let counter = Usage::new();
+    let (url, server) = mock_server(vec![("200 OK", "{}")]);
+    let url = format!("{}/data/2.5/weather?q=Berlin&appid=A", url);
+    let (response, _) = crate::fetch::send_with(None, Some(&counter), &url, get).unwrap();
+    assert!(response.is_ok());
+    server.join().unwrap();
+    let (response, _) = crate::fetch::send_with(None, Some(&counter), &url, get).unwrap();
+    assert!(response.is_err());
+    assert_eq!(counter.report().calls(), 1);
+}
+
+#[test]
+fn test_keys() {
+    let _exclusive = exclusive();
+    use keys::{KeyPool, Rotation};
+    let get = |url: &str| reqwest::blocking::get(url);
+    // the first key is rejected, so the request fails over to the second one
+    let (url, server) = mock_server(vec![
+        ("401 Unauthorized", r#"{"cod":401}"#),
+        ("200 OK", "{}"),
+        ("429 Too Many Requests", r#"{"cod":429}"#),
+    ]);
+    let served = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = served.clone();
+    let pool = KeyPool::new(&["A", "B"], Rotation::RoundRobin).on_served(move |s| {
+        log.lock()
+            .unwrap()
+            .push((s.api_key.clone(), s.status, s.url.contains("appid=")))
+    });
+    let url = format!("{}/data/2.5/weather?q=Berlin,DE&appid=X", url);
+    let (response, key) = crate::fetch::send_with(Some(&pool), None, &url, get).unwrap();
+    assert_eq!(response.unwrap().status().as_u16(), 200);
+    assert_eq!(key, Some("B".to_string()));
+    let (response, key) = crate::fetch::send_with(Some(&pool), None, &url, get).unwrap();
+    assert_eq!(response.unwrap().status().as_u16(), 429);
+    assert_eq!(key, Some("B".to_string()));
+    let requests = server.join().unwrap();
+    assert!(requests[0].contains("appid=A") && requests[1].contains("appid=B"));
+    assert!(!requests.iter().any(|r| r.contains("appid=X")));
+    assert_eq!(
+        *served.lock().unwrap(),
+        [
+            ("A".to_string(), 401, true),
+            ("B".to_string(), 200, true),
+            ("B".to_string(), 429, true)
+        ]
+    );
+    let status = pool.status();
+    assert!(status[0].invalid && !status[0].resting && status[0].calls == 1);
+    assert!(!status[1].invalid && status[1].resting && status[1].calls == 2);
+    assert_eq!(
+        crate::fetch::send_with(Some(&pool), None, &url, get)
+            .unwrap_err()
+            .to_string(),
+        "no usable API key in pool"
+    );
+    // init() does not retry without a usable key before the next poll
+    keys::enable(KeyPool::new(&[], Rotation::RoundRobin));
+    let e = blocking::weather("Berlin,DE", "metric", "en", "X").unwrap_err();
+    let receiver = init("Berlin,DE", "metric", "en", "X", 1);
+    std::thread::sleep(Duration::from_millis(100));
+    keys::disable();
+    assert_eq!(e, "no usable API key in pool");
+    assert_eq!(receiver.try_iter().count(), 2);
+    // rotation
+    let pool = KeyPool::new(&["A", "B", "C"], Rotation::RoundRobin);
+    let first: Vec<String> = (0..4).map(|_| pool.candidates()[0].clone()).collect();
+    assert_eq!(first, ["A", "B", "C", "A"]);
+    let pool = KeyPool::new(&["A", "B", "C"], Rotation::RemainingQuota)
+        .with_quota("A", 10)
+        .with_quota("B", 20);
+    assert_eq!(pool.candidates(), ["C", "B", "A"]);
+    let pool = pool.with_quota("C", 0);
+    assert_eq!(pool.candidates(), ["B", "A"]);
+    assert_eq!(pool.status()[2].remaining, Some(0));
+    assert_eq!(
+        keys::with_key("http://host/path?q=M%C3%BCnchen&appid=X&units=metric", "Y").unwrap(),
+        "http://host/path?q=M%C3%BCnchen&units=metric&appid=Y"
+    );
+}
+
 // this is a list of city IDs
 // (generated from https://bulk.openweathermap.org/sample/current.city.list.min.json.gz)
 const CITIES: &[u64] = &[
@@ -0,0 +1,241 @@
+//! Weather map tiles for overlaying clouds, precipitation, pressure, wind and temperature
+//! onto slippy maps (see [Weather Maps 1.0](https://openweathermap.org/api/weathermaps)
+//! and [Weather Maps 2.0](https://openweathermap.org/api/weather-map-2)).
+
+use crate::fetch::{get_bytes, spawn};
+use reqwest::Url;
+use std::f64::consts::PI;
+
+/// Highest zoom level a `Tile` can have (the tile columns and rows of higher levels would
+/// not fit into `u32`)
+pub const MAX_ZOOM: u8 = 31;
+
+/// Layers of Weather Maps 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Clouds
+    Clouds,
+    /// Precipitation
+    Precipitation,
+    /// Sea level pressure
+    Pressure,
+    /// Wind speed
+    Wind,
+    /// Temperature
+    Temperature,
+}
+
+impl Layer {
+    /// Layer name as used within the tile URL.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layer::Clouds => "clouds_new",
+            Layer::Precipitation => "precipitation_new",
+            Layer::Pressure => "pressure_new",
+            Layer::Wind => "wind_new",
+            Layer::Temperature => "temp_new",
+        }
+    }
+}
+
+/// Layers of Weather Maps 2.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer2 {
+    /// Convective precipitation, mm
+    ConvectivePrecipitation,
+    /// Precipitation intensity, mm/s
+    PrecipitationIntensity,
+    /// Accumulated precipitation, mm
+    AccumulatedPrecipitation,
+    /// Accumulated precipitation - rain, mm
+    AccumulatedRain,
+    /// Accumulated precipitation - snow, mm
+    AccumulatedSnow,
+    /// Depth of snow, m
+    SnowDepth,
+    /// Wind speed at an altitude of 10 meters, m/s
+    WindSpeed,
+    /// Joint display of speed wind (color) and wind direction (arrows), m/s
+    Wind,
+    /// Atmospheric pressure on mean sea level, hPa
+    Pressure,
+    /// Air temperature at a height of 2 meters, °C
+    Temperature,
+    /// Temperature of a dew point, °C
+    DewPoint,
+    /// Soil temperature 0-10 cm, K
+    SoilTemperature,
+    /// Soil temperature >10 cm, K
+    DeepSoilTemperature,
+    /// Relative humidity, %
+    Humidity,
+    /// Cloudiness, %
+    Clouds,
+}
+
+impl Layer2 {
+    /// Layer code as used within the tile URL.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layer2::ConvectivePrecipitation => "PAC0",
+            Layer2::PrecipitationIntensity => "PR0",
+            Layer2::AccumulatedPrecipitation => "PA0",
+            Layer2::AccumulatedRain => "PAR0",
+            Layer2::AccumulatedSnow => "PAS0",
+            Layer2::SnowDepth => "SD0",
+            Layer2::WindSpeed => "WS10",
+            Layer2::Wind => "WND",
+            Layer2::Pressure => "APM",
+            Layer2::Temperature => "TA2",
+            Layer2::DewPoint => "TD2",
+            Layer2::SoilTemperature => "TS0",
+            Layer2::DeepSoilTemperature => "TS10",
+            Layer2::Humidity => "HRD0",
+            Layer2::Clouds => "CL",
+        }
+    }
+}
+
+/// Position of a map tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    /// Zoom level
+    pub z: u8,
+    /// Tile column
+    pub x: u32,
+    /// Tile row
+    pub y: u32,
+}
+
+impl Tile {
+    /// Tile which contains the given geographical coordinate at zoom level `z`.
+    /// Latitudes beyond ±85.0511° (the limit of the Web Mercator projection) and zoom levels
+    /// beyond `MAX_ZOOM` are clamped.
+    pub fn from_coord(lat: f64, lon: f64, z: u8) -> Tile {
+        let z = z.min(MAX_ZOOM);
+        let n = f64::from(1u32 << z);
+        let max = (n - 1.0).max(0.0);
+        let lat = lat.clamp(-85.0511, 85.0511).to_radians();
+        let x = ((lon + 180.0) / 360.0 * n).floor().clamp(0.0, max);
+        let y = ((1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n)
+            .floor()
+            .clamp(0.0, max);
+        Tile {
+            z,
+            x: x as u32,
+            y: y as u32,
+        }
+    }
+}
+
+/// Additional parameters of Weather Maps 2.0 tiles
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Time of the displayed data, unix, UTC (current data if `None`)
+    pub date: Option<i64>,
+    /// Opacity of the layer from `0.0` to `1.0`
+    pub opacity: Option<f64>,
+    /// Custom color palette given as `value:color` pairs (e.g. `"0:FF0000;10:00FF00"`)
+    pub palette: Option<String>,
+    /// Fill values beyond the palette with its boundary colors
+    pub fill_bound: Option<bool>,
+    /// Step of the wind arrows in pixels (only for `Layer2::Wind`)
+    pub arrow_step: Option<u32>,
+    /// Use the wind speed to scale the wind arrows (only for `Layer2::Wind`)
+    pub use_norm: Option<bool>,
+}
+
+/// URL of a Weather Maps 1.0 tile.
+pub fn url(layer: Layer, tile: &Tile, api_key: &str) -> String {
+    format!(
+        "http://tile.openweathermap.org/map/{}/{}/{}/{}.png?appid={}",
+        layer.name(),
+        tile.z,
+        tile.x,
+        tile.y,
+        api_key
+    )
+}
+
+/// URL of a Weather Maps 2.0 tile (with the options percent-encoded).
+pub fn url2(layer: Layer2, tile: &Tile, options: &Options, api_key: &str) -> String {
+    let mut url = Url::parse(&format!(
+        "http://maps.openweathermap.org/maps/2.0/weather/{}/{}/{}/{}",
+        layer.name(),
+        tile.z,
+        tile.x,
+        tile.y
+    ))
+    .unwrap();
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("appid", api_key);
+        if let Some(date) = options.date {
+            query.append_pair("date", &date.to_string());
+        }
+        if let Some(opacity) = options.opacity {
+            query.append_pair("opacity", &opacity.to_string());
+        }
+        if let Some(palette) = &options.palette {
+            query.append_pair("palette", palette);
+        }
+        if let Some(fill_bound) = options.fill_bound {
+            query.append_pair("fill_bound", &fill_bound.to_string());
+        }
+        if let Some(arrow_step) = options.arrow_step {
+            query.append_pair("arrow_step", &arrow_step.to_string());
+        }
+        if let Some(use_norm) = options.use_norm {
+            query.append_pair("use_norm", &use_norm.to_string());
+        }
+    }
+    url.to_string()
+}
+
+/// Fetch a Weather Maps 1.0 tile once.
+/// Returns the PNG image data in a *future*.
+/// #### Return value
+/// - ⇒ `Ok(Vec<u8>)`: PNG image data
+/// - ⇒ `Err(String)`: Error message about any occured http issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+pub async fn tile(layer: Layer, tile: &Tile, api_key: &str) -> Result<Vec<u8>, String> {
+    let url = url(layer, tile, api_key);
+    spawn(move || get_bytes(&url)).await
+}
+
+/// Fetch a Weather Maps 2.0 tile once.
+/// Returns the PNG image data in a *future*.
+/// #### Return value
+/// - ⇒ `Ok(Vec<u8>)`: PNG image data
+/// - ⇒ `Err(String)`: Error message about any occured http issue
+///     - e.g. `401 Unauthorized` if your API key is invalid
+pub async fn tile2(
+    layer: Layer2,
+    tile: &Tile,
+    options: &Options,
+    api_key: &str,
+) -> Result<Vec<u8>, String> {
+    let url = url2(layer, tile, options, api_key);
+    spawn(move || get_bytes(&url)).await
+}
+
+/// synchronous functions
+pub mod blocking {
+    use super::*;
+    use futures::executor;
+
+    /// Fetches a Weather Maps 1.0 tile once and returns the PNG image data.
+    pub fn tile(layer: Layer, tile: &Tile, api_key: &str) -> Result<Vec<u8>, String> {
+        executor::block_on(super::tile(layer, tile, api_key))
+    }
+
+    /// Fetches a Weather Maps 2.0 tile once and returns the PNG image data.
+    pub fn tile2(
+        layer: Layer2,
+        tile: &Tile,
+        options: &Options,
+        api_key: &str,
+    ) -> Result<Vec<u8>, String> {
+        executor::block_on(super::tile2(layer, tile, options, api_key))
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Unit system of a weather report as requested by the `units` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// `"standard"`: meters, m/s, K, etc. (default of the API if no `units` are given)
+    #[default]
+    Standard,
+    /// `"metric"`: meters, m/s, °C, etc.
+    Metric,
+    /// `"imperial"`: miles, mi/h, °F, etc.
+    Imperial,
+}
+
+impl Units {
+    /// Name as used for the `units` parameter of `init()` or `weather()`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Units::Standard => "standard",
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        }
+    }
+
+    /// Whether this is the default unit system of the API.
+    pub(crate) fn is_standard(&self) -> bool {
+        *self == Units::Standard
+    }
+
+    /// Converts a temperature of this unit system into °C.
+    pub(crate) fn to_celsius(self, temp: f64) -> f64 {
+        match self {
+            Units::Standard => temp - 273.15,
+            Units::Metric => temp,
+            Units::Imperial => (temp - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    /// Converts a temperature in °C into this unit system.
+    pub(crate) fn celsius_to_units(self, celsius: f64) -> f64 {
+        match self {
+            Units::Standard => celsius + 273.15,
+            Units::Metric => celsius,
+            Units::Imperial => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// Converts a speed of this unit system into m/s.
+    pub(crate) fn to_meter_per_second(self, speed: f64) -> f64 {
+        match self {
+            Units::Standard | Units::Metric => speed,
+            Units::Imperial => speed * 0.44704,
+        }
+    }
+
+    /// Converts a speed in m/s into this unit system.
+    pub(crate) fn meter_per_second_to_units(self, speed: f64) -> f64 {
+        match self {
+            Units::Standard | Units::Metric => speed,
+            Units::Imperial => speed / 0.44704,
+        }
+    }
+}
+
+impl FromStr for Units {
+    type Err = String;
+
+    fn from_str(units: &str) -> Result<Self, Self::Err> {
+        match units {
+            "standard" | "" => Ok(Units::Standard),
+            "metric" => Ok(Units::Metric),
+            "imperial" => Ok(Units::Imperial),
+            _ => Err(format!("unknown units '{}'", units)),
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
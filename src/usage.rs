@@ -0,0 +1,214 @@
+//! Accounting of the calls sent to *OpenWeatherMap* per endpoint, API key and day.
+//!
+//! Once enabled, every request of this crate which is not answered from a cache (including
+//! the polling threads of `init()`) is counted as soon as *OpenWeatherMap* responded to it.
+//! Requests which failed to connect are not counted.
+//!
+//! ```no_run
+//! use openweathermap::usage::{self, Usage};
+//!
+//! usage::enable(Usage::persistent("usage.json").unwrap());
+//! openweathermap::blocking::weather("Berlin,DE", "metric", "en", "<APIKEY>").unwrap();
+//! let report = openweathermap::usage().unwrap();
+//! println!("calls this month: {}", report.month(&usage::this_month()).calls());
+//! ```
+
+use crate::cache::Key;
+use crate::datetime::civil_from_days;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Calls of one endpoint with one API key on one day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UsageEntry {
+    /// Day in format `YYYY-MM-DD` (UTC)
+    pub date: String,
+    /// Host and path of the requested API (e.g. `"api.openweathermap.org/data/2.5/weather"`)
+    pub endpoint: String,
+    /// API key the calls were made with
+    pub api_key: String,
+    /// Number of calls
+    pub calls: u64,
+}
+
+/// Usage counters which can be narrowed down by day, month, endpoint and API key
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UsageReport {
+    /// Counters ordered by date, endpoint and API key
+    pub entries: Vec<UsageEntry>,
+}
+
+impl UsageReport {
+    fn filter<F: Fn(&UsageEntry) -> bool>(&self, f: F) -> UsageReport {
+        UsageReport {
+            entries: self.entries.iter().filter(|e| f(e)).cloned().collect(),
+        }
+    }
+
+    /// Total number of calls.
+    pub fn calls(&self) -> u64 {
+        self.entries.iter().map(|e| e.calls).sum()
+    }
+
+    /// Calls on a day given in format `YYYY-MM-DD`.
+    pub fn day(&self, date: &str) -> UsageReport {
+        self.filter(|e| e.date == date)
+    }
+
+    /// Calls within a month given in format `YYYY-MM`.
+    pub fn month(&self, month: &str) -> UsageReport {
+        self.filter(|e| {
+            e.date
+                .strip_prefix(month)
+                .is_some_and(|day| day.starts_with('-'))
+        })
+    }
+
+    /// Calls of an endpoint (e.g. `"api.openweathermap.org/data/2.5/weather"`).
+    pub fn endpoint(&self, endpoint: &str) -> UsageReport {
+        self.filter(|e| e.endpoint == endpoint)
+    }
+
+    /// Calls made with an API key.
+    pub fn api_key(&self, api_key: &str) -> UsageReport {
+        self.filter(|e| e.api_key == api_key)
+    }
+}
+
+type Counters = BTreeMap<(String, String, String), u64>;
+
+/// Call counter which can be shared between threads (clones share the same counters)
+#[derive(Clone, Default)]
+pub struct Usage {
+    counters: Arc<Mutex<Counters>>,
+    path: Option<PathBuf>,
+}
+
+impl Usage {
+    /// Creates counters kept in memory only.
+    pub fn new() -> Usage {
+        Usage::default()
+    }
+
+    /// Creates counters which are saved to the JSON file `path` after every call and
+    /// continues the counters already stored there (the file contains the API keys in plain
+    /// text).
+    pub fn persistent<P: AsRef<Path>>(path: P) -> Result<Usage, String> {
+        let mut counters = Counters::new();
+        if path.as_ref().exists() {
+            let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let entries: Vec<UsageEntry> =
+                serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            for e in entries {
+                counters.insert((e.date, e.endpoint, e.api_key), e.calls);
+            }
+        }
+        Ok(Usage {
+            counters: Arc::new(Mutex::new(counters)),
+            path: Some(path.as_ref().to_path_buf()),
+        })
+    }
+
+    /// Counts a call of `url` today.
+    pub fn record(&self, url: &str) -> Result<(), String> {
+        self.record_on(&today(), url)
+    }
+
+    /// Counts a call of `url` on `date` (format `YYYY-MM-DD`).
+    pub fn record_on(&self, date: &str, url: &str) -> Result<(), String> {
+        let endpoint = Key::from_url(url)?.endpoint;
+        let api_key = Url::parse(url)
+            .map_err(|e| e.to_string())?
+            .query_pairs()
+            .find(|(name, _)| name == "appid")
+            .map(|(_, value)| value.into_owned())
+            .unwrap_or_default();
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        *counters
+            .entry((date.to_string(), endpoint, api_key))
+            .or_insert(0) += 1;
+        match &self.path {
+            Some(path) => save(path, &entries(&counters)),
+            None => Ok(()),
+        }
+    }
+
+    /// Report of all calls counted.
+    pub fn report(&self) -> UsageReport {
+        UsageReport {
+            entries: entries(&self.counters.lock().unwrap_or_else(|e| e.into_inner())),
+        }
+    }
+
+    /// Resets all counters.
+    pub fn clear(&self) -> Result<(), String> {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        counters.clear();
+        match &self.path {
+            Some(path) => save(path, &[]),
+            None => Ok(()),
+        }
+    }
+}
+
+fn entries(counters: &Counters) -> Vec<UsageEntry> {
+    counters
+        .iter()
+        .map(|((date, endpoint, api_key), calls)| UsageEntry {
+            date: date.clone(),
+            endpoint: endpoint.clone(),
+            api_key: api_key.clone(),
+            calls: *calls,
+        })
+        .collect()
+}
+
+/// Writes `entries` to `path` replacing its content at once.
+fn save(path: &Path, entries: &[UsageEntry]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, json).map_err(|e| e.to_string())?;
+    fs::rename(&temp, path).map_err(|e| e.to_string())
+}
+
+/// Date of a unix time in format `YYYY-MM-DD` (UTC).
+pub fn date(unix: i64) -> String {
+    let (year, month, day) = civil_from_days(unix.div_euclid(86400));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Current day in format `YYYY-MM-DD` (UTC).
+pub fn today() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    date(now)
+}
+
+/// Current month in format `YYYY-MM` (UTC).
+pub fn this_month() -> String {
+    today()[..7].to_string()
+}
+
+static USAGE: Mutex<Option<Usage>> = Mutex::new(None);
+
+/// Counts all following calls of this crate in `usage`.
+pub fn enable(usage: Usage) {
+    *USAGE.lock().unwrap_or_else(|e| e.into_inner()) = Some(usage);
+}
+
+/// Stops counting calls.
+pub fn disable() {
+    *USAGE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Counters currently enabled.
+pub fn current() -> Option<Usage> {
+    USAGE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
@@ -0,0 +1,217 @@
+use crate::{CurrentWeather, Units, Wind};
+
+/// Beaufort wind force
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Beaufort {
+    /// 0: < 0.5 m/s
+    Calm,
+    /// 1: 0.5–1.5 m/s
+    LightAir,
+    /// 2: 1.6–3.3 m/s
+    LightBreeze,
+    /// 3: 3.4–5.4 m/s
+    GentleBreeze,
+    /// 4: 5.5–7.9 m/s
+    ModerateBreeze,
+    /// 5: 8.0–10.7 m/s
+    FreshBreeze,
+    /// 6: 10.8–13.8 m/s
+    StrongBreeze,
+    /// 7: 13.9–17.1 m/s
+    NearGale,
+    /// 8: 17.2–20.7 m/s
+    Gale,
+    /// 9: 20.8–24.4 m/s
+    StrongGale,
+    /// 10: 24.5–28.4 m/s
+    Storm,
+    /// 11: 28.5–32.6 m/s
+    ViolentStorm,
+    /// 12: ≥ 32.7 m/s
+    Hurricane,
+}
+
+use Beaufort::*;
+
+/// Forces with their lower speed limit (m/s) and descriptions in English, German, French and Spanish
+const SCALE: [(Beaufort, f64, [&str; 4]); 13] = [
+    (Calm, 0.0, ["calm", "Windstille", "calme", "calma"]),
+    (
+        LightAir,
+        0.5,
+        ["light air", "leiser Zug", "très légère brise", "ventolina"],
+    ),
+    (
+        LightBreeze,
+        1.6,
+        ["light breeze", "leichte Brise", "légère brise", "flojito"],
+    ),
+    (
+        GentleBreeze,
+        3.4,
+        ["gentle breeze", "schwache Brise", "petite brise", "flojo"],
+    ),
+    (
+        ModerateBreeze,
+        5.5,
+        [
+            "moderate breeze",
+            "mäßige Brise",
+            "jolie brise",
+            "bonancible",
+        ],
+    ),
+    (
+        FreshBreeze,
+        8.0,
+        ["fresh breeze", "frische Brise", "bonne brise", "fresquito"],
+    ),
+    (
+        StrongBreeze,
+        10.8,
+        ["strong breeze", "starker Wind", "vent frais", "fresco"],
+    ),
+    (
+        NearGale,
+        13.9,
+        ["near gale", "steifer Wind", "grand frais", "frescachón"],
+    ),
+    (
+        Gale,
+        17.2,
+        ["gale", "stürmischer Wind", "coup de vent", "temporal"],
+    ),
+    (
+        StrongGale,
+        20.8,
+        [
+            "strong gale",
+            "Sturm",
+            "fort coup de vent",
+            "temporal fuerte",
+        ],
+    ),
+    (
+        Storm,
+        24.5,
+        ["storm", "schwerer Sturm", "tempête", "temporal duro"],
+    ),
+    (
+        ViolentStorm,
+        28.5,
+        [
+            "violent storm",
+            "orkanartiger Sturm",
+            "violente tempête",
+            "temporal muy duro",
+        ],
+    ),
+    (
+        Hurricane,
+        32.7,
+        ["hurricane", "Orkan", "ouragan", "temporal huracanado"],
+    ),
+];
+
+impl Beaufort {
+    /// Beaufort force of a wind speed in m/s.
+    pub fn from_meter_per_second(speed: f64) -> Beaufort {
+        SCALE
+            .iter()
+            .rev()
+            .find(|(_, min, _)| speed >= *min)
+            .map_or(Calm, |(b, _, _)| *b)
+    }
+
+    /// Force number from `0` to `12`.
+    pub fn force(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Description in the given language (`"en"`, `"de"`, `"fr"` or `"es"`, English otherwise).
+    pub fn description(&self, lang: &str) -> &'static str {
+        let lang = match lang {
+            "de" => 1,
+            "fr" => 2,
+            "es" => 3,
+            _ => 0,
+        };
+        SCALE[*self as usize].2[lang]
+    }
+}
+
+/// Number of points of a compass rose
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompassPoints {
+    /// N, NE, E, …
+    P8 = 8,
+    /// N, NNE, NE, ENE, …
+    P16 = 16,
+    /// N, NbE, NNE, NEbN, …
+    P32 = 32,
+}
+
+/// Names of the 32 compass points clockwise from north
+const COMPASS: [&str; 32] = [
+    "N", "NbE", "NNE", "NEbN", "NE", "NEbE", "ENE", "EbN", "E", "EbS", "ESE", "SEbE", "SE", "SEbS",
+    "SSE", "SbE", "S", "SbW", "SSW", "SWbS", "SW", "SWbW", "WSW", "WbS", "W", "WbN", "WNW", "NWbW",
+    "NW", "NWbN", "NNW", "NbW",
+];
+
+/// Name of the compass point nearest to a direction in degrees.
+pub fn compass(deg: f64, points: CompassPoints) -> &'static str {
+    let n = points as usize;
+    let index = (deg.rem_euclid(360.0) / (360.0 / n as f64)).round() as usize % n;
+    COMPASS[index * (32 / n)]
+}
+
+impl Wind {
+    /// Beaufort force of `speed`.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn beaufort(&self, units: Units) -> Beaufort {
+        Beaufort::from_meter_per_second(units.to_meter_per_second(self.speed))
+    }
+
+    /// Beaufort force of `gust`.
+    /// #### Parameters
+    /// - `units`: unit system the weather report was fetched in
+    pub fn gust_beaufort(&self, units: Units) -> Option<Beaufort> {
+        Some(Beaufort::from_meter_per_second(
+            units.to_meter_per_second(self.gust?),
+        ))
+    }
+
+    /// Name of the compass point the wind is blowing from.
+    pub fn compass(&self, points: CompassPoints) -> Option<&'static str> {
+        Some(compass(self.deg?, points))
+    }
+
+    /// Vector components `(u, v)` of the wind in the speed unit of the report:
+    /// `u` is positive for wind blowing towards east, `v` for wind blowing towards north.
+    pub fn components(&self) -> Option<(f64, f64)> {
+        let deg = self.deg?.to_radians();
+        Some((-self.speed * deg.sin(), -self.speed * deg.cos()))
+    }
+
+    /// Ratio of `gust` and `speed`.
+    /// `None` without gust or calm wind.
+    pub fn gust_factor(&self) -> Option<f64> {
+        match self.speed > 0.0 {
+            true => Some(self.gust? / self.speed),
+            false => None,
+        }
+    }
+}
+
+impl CurrentWeather {
+    /// Beaufort force of the wind speed.
+    pub fn beaufort(&self) -> Beaufort {
+        self.wind.beaufort(self.units)
+    }
+
+    /// Beaufort force of the gusts.
+    pub fn gust_beaufort(&self) -> Option<Beaufort> {
+        self.wind.gust_beaufort(self.units)
+    }
+}
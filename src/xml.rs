@@ -0,0 +1,197 @@
+//! Parse current weather documents in XML format (see `weather_document()` with `Mode::Xml`).
+
+use crate::datetime::days_from_civil;
+use crate::{Clouds, Condition, Coord, CurrentWeather, Main, Sys, Units, Volume, Weather, Wind};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+
+/// Attributes of an element
+type Attributes = HashMap<String, String>;
+
+fn attributes(element: &BytesStart) -> Result<Attributes, String> {
+    let mut attributes = Attributes::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        attributes.insert(
+            String::from_utf8_lossy(attribute.key.as_ref()).to_string(),
+            attribute
+                .unescape_value()
+                .map_err(|e| e.to_string())?
+                .to_string(),
+        );
+    }
+    Ok(attributes)
+}
+
+/// Parses a number from attribute `key` of an element.
+fn number(attributes: &Attributes, element: &str, key: &str) -> Result<f64, String> {
+    match attributes.get(key) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("invalid value '{}' of {}/@{}", value, element, key)),
+        None => Err(format!("missing {}/@{}", element, key)),
+    }
+}
+
+/// Converts a UTC time like `2017-01-30T07:40:36` into unix time.
+fn unix_time(time: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid time '{}'", time);
+    let field = |range: std::ops::Range<usize>| -> Result<i64, String> {
+        time.get(range)
+            .and_then(|f| f.parse().ok())
+            .ok_or_else(invalid)
+    };
+    let (y, m, d) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hh, mm, ss) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    Ok(days_from_civil(y, m, d) * 86400 + hh * 3600 + mm * 60 + ss)
+}
+
+/// Parses a current weather document in XML format into the same nested struct
+/// which the JSON API delivers.
+/// #### Return value
+/// - ⇒ `Ok(CurrentWeather)`: weather information in a nested struct called `CurrentWeather`
+/// - ⇒ `Err(String)`: Error message about any xml issue or missing element
+pub fn parse(document: &str) -> Result<CurrentWeather, String> {
+    let mut reader = Reader::from_str(document);
+    reader.config_mut().trim_text(true);
+    let mut elements: HashMap<String, Attributes> = HashMap::new();
+    let mut texts: HashMap<String, String> = HashMap::new();
+    let mut path: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                path.push(String::from_utf8_lossy(e.name().as_ref()).to_string());
+                elements.insert(path.join("/"), attributes(&e)?);
+            }
+            Event::Empty(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                let key = path.iter().chain(Some(&name)).cloned().collect::<Vec<_>>();
+                elements.insert(key.join("/"), attributes(&e)?);
+            }
+            Event::Text(t) => {
+                texts.insert(
+                    path.join("/"),
+                    t.unescape().map_err(|e| e.to_string())?.to_string(),
+                );
+            }
+            Event::End(_) => {
+                path.pop();
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    let element = |name: &str| -> Result<&Attributes, String> {
+        elements
+            .get(name)
+            .ok_or_else(|| format!("missing element {}", name))
+    };
+    let optional = |name: &str, key: &str| -> Option<f64> {
+        elements
+            .get(name)
+            .and_then(|a| a.get(key))
+            .and_then(|v| v.parse().ok())
+    };
+    let text = |name: &str| -> Result<&String, String> {
+        texts
+            .get(name)
+            .ok_or_else(|| format!("missing element {}", name))
+    };
+
+    let city = element("current/city")?;
+    let coord = element("current/city/coord")?;
+    let sun = |key: &str| -> Result<Option<i64>, String> {
+        match elements.get("current/city/sun").and_then(|s| s.get(key)) {
+            Some(time) => unix_time(time).map(Some),
+            None => Ok(None),
+        }
+    };
+    let temperature = element("current/temperature")?;
+    let weather = element("current/weather")?;
+    let id = number(weather, "weather", "number")? as u64;
+    // precipitation is either absent (`mode="no"`) or rain/snow volume of the last 1h or 3h
+    let precipitation = elements.get("current/precipitation");
+    let volume = |mode: &str| -> Option<Volume> {
+        let p = precipitation.filter(|p| p.get("mode").map(String::as_str) == Some(mode))?;
+        let value = p.get("value").and_then(|v| v.parse().ok());
+        match p.get("unit").map(String::as_str) {
+            Some("3h") => Some(Volume {
+                h1: None,
+                h3: value,
+                extra: Default::default(),
+            }),
+            _ => Some(Volume {
+                h1: value,
+                h3: None,
+                extra: Default::default(),
+            }),
+        }
+    };
+
+    Ok(CurrentWeather {
+        coord: Coord {
+            lon: number(coord, "coord", "lon")?,
+            lat: number(coord, "coord", "lat")?,
+        },
+        weather: vec![Weather {
+            id,
+            main: Condition::from_id(id).main().to_string(),
+            description: weather.get("value").cloned().unwrap_or_default(),
+            icon: weather.get("icon").cloned().unwrap_or_default(),
+            extra: Default::default(),
+        }],
+        base: "xml".to_string(),
+        main: Main {
+            temp: number(temperature, "temperature", "value")?,
+            feels_like: number(element("current/feels_like")?, "feels_like", "value")?,
+            pressure: number(element("current/pressure")?, "pressure", "value")?,
+            humidity: number(element("current/humidity")?, "humidity", "value")?,
+            temp_min: number(temperature, "temperature", "min")?,
+            temp_max: number(temperature, "temperature", "max")?,
+            sea_level: None,
+            grnd_level: None,
+            extra: Default::default(),
+        },
+        visibility: optional("current/visibility", "value").map(|v| v as u64),
+        wind: Wind {
+            speed: number(element("current/wind/speed")?, "speed", "value")?,
+            deg: optional("current/wind/direction", "value"),
+            gust: optional("current/wind/gusts", "value"),
+            extra: Default::default(),
+        },
+        clouds: Clouds {
+            all: number(element("current/clouds")?, "clouds", "value")?,
+            extra: Default::default(),
+        },
+        rain: volume("rain"),
+        snow: volume("snow"),
+        dt: unix_time(
+            element("current/lastupdate")?
+                .get("value")
+                .ok_or("missing lastupdate/@value")?,
+        )?,
+        sys: Sys {
+            type_: None,
+            id: None,
+            message: None,
+            country: texts.get("current/city/country").cloned(),
+            sunrise: sun("rise")?,
+            sunset: sun("set")?,
+            extra: Default::default(),
+        },
+        timezone: text("current/city/timezone")?
+            .parse()
+            .map_err(|_| "invalid timezone".to_string())?,
+        id: number(city, "city", "id")? as u64,
+        name: city.get("name").cloned().unwrap_or_default(),
+        cod: 200,
+        // the document tells the unit system by the unit of its temperatures
+        units: match temperature.get("unit").map(String::as_str) {
+            Some("celsius") => Units::Metric,
+            Some("fahrenheit") => Units::Imperial,
+            _ => Units::Standard,
+        },
+        extra: Default::default(),
+    })
+}